@@ -0,0 +1,157 @@
+//! Derive macros for [`logos_iterator`](https://docs.rs/logos_iterator/)
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+/// Derive `logos_iterator::TokenName`
+///
+/// Each variant is named with `#[name("...")]`, or its own name by default
+#[proc_macro_derive(TokenName, attributes(name))]
+pub fn token_name(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let ident = &input.ident;
+
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return syn::Error::new_spanned(&input, "TokenName can only be derived for enums")
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let mut arms = vec![];
+    for variant in &data.variants {
+        let name = variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path.is_ident("name"))
+            .map(|attr| attr.parse_args::<LitStr>())
+            .transpose();
+
+        let name = match name {
+            Ok(Some(name)) => name.value(),
+            Ok(None) => variant.ident.to_string(),
+            Err(err) => return err.to_compile_error().into(),
+        };
+
+        let ident = &variant.ident;
+        let pattern = match &variant.fields {
+            Fields::Unit => quote!(Self::#ident),
+            Fields::Unnamed(..) => quote!(Self::#ident(..)),
+            Fields::Named(..) => quote!(Self::#ident { .. }),
+        };
+        arms.push(quote!(#pattern => #name,));
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let body = if arms.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote!(match self { #(#arms)* })
+    };
+
+    let tokens = quote! {
+        impl #impl_generics ::logos_iterator::TokenName for #ident #ty_generics #where_clause {
+            fn token_name(&self) -> &'static str {
+                #body
+            }
+        }
+    };
+    tokens.into()
+}
+
+/// Derive `logos_iterator::HasSpan`
+///
+/// The span is the join of the spans of the fields (of each variant, for an
+/// enum). Fields that don't have a span are skipped with `#[span(skip)]`
+#[proc_macro_derive(HasSpan, attributes(span))]
+pub fn has_span(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let ident = &input.ident;
+
+    let variants = match &input.data {
+        Data::Struct(data) => vec![(quote!(Self), &data.fields, &input.ident)],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|v| {
+                let ident = &v.ident;
+                (quote!(Self::#ident), &v.fields, ident)
+            })
+            .collect(),
+        Data::Union(..) => {
+            return syn::Error::new_spanned(&input, "HasSpan can't be derived for unions")
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let mut arms = vec![];
+    for (path, fields, name) in variants {
+        let mut bindings = vec![];
+        let mut patterns = vec![];
+        for (i, field) in fields.iter().enumerate() {
+            let skip = match skipped(field) {
+                Ok(skip) => skip,
+                Err(err) => return err.to_compile_error().into(),
+            };
+            let binding = quote::format_ident!("__field{}", i);
+            let pattern = match (&field.ident, skip) {
+                (Some(ident), false) => quote!(#ident: #binding),
+                (Some(ident), true) => quote!(#ident: _),
+                (None, false) => quote!(#binding),
+                (None, true) => quote!(_),
+            };
+            patterns.push(pattern);
+            if !skip {
+                bindings.push(binding);
+            }
+        }
+
+        let (first, rest) = match bindings.split_first() {
+            Some(split) => split,
+            None => {
+                let message = "HasSpan needs at least one field that isn't skipped";
+                return syn::Error::new_spanned(name, message)
+                    .to_compile_error()
+                    .into();
+            }
+        };
+        let pattern = match fields {
+            Fields::Named(..) => quote!(#path { #(#patterns),* }),
+            Fields::Unnamed(..) => quote!(#path ( #(#patterns),* )),
+            Fields::Unit => quote!(#path),
+        };
+        arms.push(quote! {
+            #pattern => ::logos_iterator::HasSpan::span(#first)
+                #(.join(::logos_iterator::HasSpan::span(#rest)))*,
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let tokens = quote! {
+        impl #impl_generics ::logos_iterator::HasSpan for #ident #ty_generics #where_clause {
+            fn span(&self) -> ::logos_iterator::Span {
+                match self { #(#arms)* }
+            }
+        }
+    };
+    tokens.into()
+}
+
+// whether the field has `#[span(skip)]`
+fn skipped(field: &syn::Field) -> syn::Result<bool> {
+    match field.attrs.iter().find(|attr| attr.path.is_ident("span")) {
+        Some(attr) => {
+            let arg = attr.parse_args::<syn::Ident>()?;
+            if arg != "skip" {
+                return Err(syn::Error::new_spanned(arg, "expected `skip`"));
+            }
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{Span, WithSpan};
+use alloc::vec::Vec;
+
+/// `BracketError` is a problem found by [`check_brackets`](./fn.check_brackets.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BracketError {
+    /// An opener that was never closed, and the span of the end of the input
+    Unclosed { open: Span, end: Span },
+    /// A closer without an opener, and the span of the enclosing opener (if any)
+    Stray {
+        close: Span,
+        enclosing: Option<Span>,
+    },
+    /// A closer that doesn't match the innermost opener
+    Mismatched { open: Span, close: Span },
+}
+
+impl core::fmt::Display for BracketError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Unclosed { open, .. } => write!(f, "unclosed delimiter at {}", open),
+            Self::Stray { close, .. } => write!(f, "unexpected closing delimiter at {}", close),
+            Self::Mismatched { open, close } => write!(
+                f,
+                "closing delimiter at {} doesn't match the opening delimiter at {}",
+                close, open
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BracketError {}
+
+/// Check that the bracket `pairs` of `(open, close)` kinds are balanced in `tokens`
+///
+/// This returns every error found, in the order they were found. After a
+/// mismatched closer, the openers are unwound to the one it matches (if any)
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, check_brackets, BracketError, Span};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[token = "("]
+///     OpenParen,
+///     #[token = ")"]
+///     CloseParen,
+///     #[token = "["]
+///     OpenBracket,
+///     #[token = "]"]
+///     CloseBracket,
+/// }
+///
+/// let tokens = lex::<Token, _>("( [ ) ]").spanned().collect::<Vec<_>>();
+/// let pairs = [
+///     (Token::OpenParen, Token::CloseParen),
+///     (Token::OpenBracket, Token::CloseBracket),
+/// ];
+/// assert_eq!(
+///     check_brackets(&tokens, &pairs),
+///     vec![
+///         BracketError::Mismatched { open: Span::from(2..3), close: Span::from(4..5) },
+///         BracketError::Stray { close: Span::from(6..7), enclosing: None },
+///     ]
+/// );
+/// ```
+pub fn check_brackets<T>(tokens: &[WithSpan<T>], pairs: &[(T, T)]) -> Vec<BracketError>
+where
+    T: PartialEq,
+{
+    let mut errors = vec![];
+    // (index of the pair, span of the opener)
+    let mut stack: Vec<(usize, Span)> = vec![];
+
+    for token in tokens {
+        if let Some(pair) = pairs.iter().position(|(open, _)| *open == token.item) {
+            stack.push((pair, token.span));
+            continue;
+        }
+
+        let pair = match pairs.iter().position(|(_, close)| *close == token.item) {
+            Some(pair) => pair,
+            None => continue,
+        };
+
+        match stack.iter().rposition(|&(open, _)| open == pair) {
+            Some(index) if index + 1 == stack.len() => {}
+            Some(index) => {
+                let close = token.span;
+                let (_, open) = stack[stack.len() - 1];
+                errors.push(BracketError::Mismatched { open, close });
+                let end = Span::from(close.start..close.start);
+                for &(_, open) in stack[index + 1..stack.len() - 1].iter().rev() {
+                    errors.push(BracketError::Unclosed { open, end })
+                }
+                stack.truncate(index + 1);
+            }
+            None => {
+                errors.push(BracketError::Stray {
+                    close: token.span,
+                    enclosing: stack.last().map(|&(_, span)| span),
+                });
+                continue;
+            }
+        }
+        stack.pop();
+    }
+
+    let end = tokens.last().map_or(0, |k| k.span.end);
+    let end = Span::from(end..end);
+    for (_, open) in stack.into_iter().rev() {
+        errors.push(BracketError::Unclosed { open, end })
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[token = "("]
+        OpenParen,
+        #[token = ")"]
+        CloseParen,
+        #[token = "{"]
+        OpenBrace,
+        #[token = "}"]
+        CloseBrace,
+        #[regex = "[a-z]+"]
+        Word,
+    }
+
+    const PAIRS: [(Token, Token); 2] = [
+        (Token::OpenParen, Token::CloseParen),
+        (Token::OpenBrace, Token::CloseBrace),
+    ];
+
+    fn check(input: &str) -> Vec<BracketError> {
+        let tokens = crate::lex::<Token, _>(input).spanned().collect::<Vec<_>>();
+        check_brackets(&tokens, &PAIRS)
+    }
+
+    #[test]
+    fn balanced() {
+        assert!(check("a (b {c} (d)) {}").is_empty());
+        assert!(check("").is_empty());
+    }
+
+    #[test]
+    fn unbalanced() {
+        assert_eq!(
+            check("{ ( a"),
+            vec![
+                BracketError::Unclosed {
+                    open: Span::from(2..3),
+                    end: Span::from(5..5)
+                },
+                BracketError::Unclosed {
+                    open: Span::from(0..1),
+                    end: Span::from(5..5)
+                },
+            ]
+        );
+
+        assert_eq!(
+            check("( ) }"),
+            vec![BracketError::Stray {
+                close: Span::from(4..5),
+                enclosing: None
+            }]
+        );
+
+        assert_eq!(
+            check("( } )"),
+            vec![BracketError::Stray {
+                close: Span::from(2..3),
+                enclosing: Some(Span::from(0..1))
+            }]
+        );
+
+        assert_eq!(
+            check("{ ( ( }"),
+            vec![
+                BracketError::Mismatched {
+                    open: Span::from(4..5),
+                    close: Span::from(6..7)
+                },
+                BracketError::Unclosed {
+                    open: Span::from(2..3),
+                    end: Span::from(6..6)
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{Lexer, Span, Spanned, WithSpan};
+use alloc::vec::Vec;
+
+/// `CharIndex` maps between byte offsets and `char` offsets in a source
+///
+/// This only stores where the non-ASCII chars are, so ASCII sources are cheap
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CharIndex {
+    wide: Vec<Wide>,
+    len: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Wide {
+    byte: usize,
+    char: usize,
+    len: usize,
+}
+
+impl CharIndex {
+    /// Create a new index over `source`
+    pub fn new(source: &str) -> Self {
+        let wide = source
+            .char_indices()
+            .enumerate()
+            .filter(|(_, (_, c))| !c.is_ascii())
+            .map(|(char, (byte, c))| Wide {
+                byte,
+                char,
+                len: c.len_utf8(),
+            })
+            .collect();
+        Self {
+            wide,
+            len: source.len(),
+        }
+    }
+
+    /// The number of chars in the source
+    pub fn char_len(&self) -> usize {
+        self.to_char(self.len)
+    }
+
+    /// Convert a byte offset into a char offset
+    ///
+    /// An offset inside of a char is the offset of that char
+    pub fn to_char(&self, byte: usize) -> usize {
+        match self.wide.partition_point(|w| w.byte < byte) {
+            0 => byte,
+            n => {
+                let prev = self.wide[n - 1];
+                match byte.checked_sub(prev.byte + prev.len) {
+                    Some(d) => prev.char + 1 + d,
+                    None => prev.char,
+                }
+            }
+        }
+    }
+
+    /// Convert a char offset into a byte offset
+    pub fn to_byte(&self, char: usize) -> usize {
+        match self.wide.partition_point(|w| w.char < char) {
+            0 => char,
+            n => {
+                let prev = self.wide[n - 1];
+                prev.byte + prev.len + (char - prev.char - 1)
+            }
+        }
+    }
+
+    /// Convert a byte based [`Span`](./struct.Span.html) into a char based one
+    pub fn to_char_span(&self, span: Span) -> Span {
+        Span {
+            start: self.to_char(span.start),
+            end: self.to_char(span.end),
+        }
+    }
+
+    /// Convert a char based [`Span`](./struct.Span.html) into a byte based one
+    pub fn to_byte_span(&self, span: Span) -> Span {
+        Span {
+            start: self.to_byte(span.start),
+            end: self.to_byte(span.end),
+        }
+    }
+}
+
+/// A lexer that yields tokens wrapped with a char based [`Span`](./struct.Span.html)
+///
+/// This is created with [`Lexer::char_spanned`](./struct.Lexer.html#method.char_spanned)
+pub struct CharSpannedLexer<T, S>
+where
+    T: PartialEq<T> + ::logos::Logos,
+{
+    lexer: Lexer<T, S, Spanned>,
+    byte: usize,
+    char: usize,
+}
+
+impl<T, S, M> Lexer<T, S, M>
+where
+    T: PartialEq<T> + ::logos::Logos,
+{
+    /// Yield the tokens wrapped with a [`Span`](./struct.Span.html) that is the
+    /// `char` offsets into the source, rather than the byte offsets
+    pub fn char_spanned(self) -> CharSpannedLexer<T, S> {
+        CharSpannedLexer {
+            lexer: self.spanned(),
+            byte: 0,
+            char: 0,
+        }
+    }
+}
+
+impl<'a, T, S> CharSpannedLexer<T, S>
+where
+    T: PartialEq<T> + ::logos::Logos,
+    S: ::logos::source::Source<'a>,
+{
+    fn advance_to(&mut self, offset: usize) -> usize {
+        if let Some(slice) = self.lexer.inner().source.slice(self.byte..offset) {
+            self.char += ::logos::source::Slice::as_bytes(&slice)
+                .iter()
+                .filter(|&&b| b & 0xC0 != 0x80)
+                .count();
+            self.byte = offset;
+        }
+        self.char
+    }
+}
+
+impl<'a, T, S> Iterator for CharSpannedLexer<T, S>
+where
+    T: Clone + PartialEq<T>,
+    T: ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'a>,
+{
+    type Item = WithSpan<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let WithSpan { item, span } = self.lexer.next()?;
+        let start = self.advance_to(span.start);
+        let end = self.advance_to(span.end);
+        Some(WithSpan::new(item, Span { start, end }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+    }
+
+    #[test]
+    fn char_index() {
+        let input = "ab é日 cd";
+        let index = CharIndex::new(input);
+        assert_eq!(index.char_len(), input.chars().count());
+        for (char, (byte, _)) in input.char_indices().enumerate() {
+            assert_eq!(index.to_char(byte), char);
+            assert_eq!(index.to_byte(char), byte);
+        }
+
+        let spans = crate::lex::<Token, _>(input)
+            .spanned()
+            .map(|k| index.to_char_span(k.span))
+            .collect::<Vec<_>>();
+        let char_spans = crate::lex::<Token, _>(input)
+            .char_spanned()
+            .map(|k| k.span)
+            .collect::<Vec<_>>();
+        assert_eq!(spans, char_spans);
+        assert_eq!(char_spans.last(), Some(&Span { start: 6, end: 8 }));
+    }
+}
@@ -0,0 +1,214 @@
+//! Small backtracking parser combinators over a [`Cursor`](../struct.Cursor.html)
+//!
+//! A parser is a `FnMut(&mut Cursor) -> Option<O>`, that returns `None` if it
+//! didn't match. The combinators here move the cursor back to where it was when
+//! a parser they run doesn't match, so a failed parser never consumes anything
+//!
+//! ```rust
+//! # use logos::Logos;
+//! # use logos_iterator::{lex, Span, TokenStream};
+//! use logos_iterator::combinator::{alt, delimited, many, opt, spanned, token};
+//! #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+//! enum Token {
+//!     #[end]
+//!     Eof,
+//!     #[error]
+//!     Unknown,
+//!     #[regex = "[a-z]+"]
+//!     Word,
+//!     #[regex = "[0-9]+"]
+//!     Number,
+//!     #[token = ","]
+//!     Comma,
+//!     #[token = "("]
+//!     Open,
+//!     #[token = ")"]
+//!     Close,
+//! }
+//!
+//! let stream = lex::<Token, _>("(a, 1, b,) c").spanned().collect::<TokenStream<_>>();
+//!
+//! // a parenthesized list of words or numbers, with an optional trailing comma
+//! let mut item = alt(token(Token::Word), token(Token::Number));
+//! let mut list = spanned(delimited(
+//!     token(Token::Open),
+//!     many(move |cursor| {
+//!         let item = item(cursor)?;
+//!         if !cursor.at(Token::Close) {
+//!             token(Token::Comma)(cursor)?;
+//!         }
+//!         Some(item.item)
+//!     }),
+//!     token(Token::Close),
+//! ));
+//!
+//! let mut cursor = stream.cursor();
+//! let parsed = list(&mut cursor).unwrap();
+//! assert_eq!(parsed.item, vec![Token::Word, Token::Number, Token::Word]);
+//! assert_eq!(parsed.span, Span::from(0..10));
+//!
+//! // this doesn't match, so nothing is consumed
+//! assert_eq!(list(&mut cursor), None);
+//! assert_eq!(opt(token(Token::Comma))(&mut cursor), Some(None));
+//! assert_eq!(cursor.position(), 8);
+//! ```
+use crate::{Cursor, Span, TokenInput, WithSpan};
+use alloc::vec::Vec;
+
+/// Run the parser `p`, moving the `cursor` back to where it was if it doesn't match
+pub fn attempt<'a, T, O>(
+    cursor: &mut Cursor<'a, T>,
+    mut p: impl FnMut(&mut Cursor<'a, T>) -> Option<O>,
+) -> Option<O> {
+    let checkpoint = cursor.position();
+    let out = p(cursor);
+    if out.is_none() {
+        cursor.reset(checkpoint);
+    }
+    out
+}
+
+/// A parser that matches a single `kind` of token
+pub fn token<'a, T>(kind: T) -> impl FnMut(&mut Cursor<'a, T>) -> Option<&'a WithSpan<T>>
+where
+    T: PartialEq + Clone,
+{
+    move |cursor| cursor.eat(kind.clone())
+}
+
+/// A parser that optionally matches `p`
+///
+/// This always matches, with `None` if `p` didn't
+pub fn opt<'a, T, O>(
+    mut p: impl FnMut(&mut Cursor<'a, T>) -> Option<O>,
+) -> impl FnMut(&mut Cursor<'a, T>) -> Option<Option<O>> {
+    move |cursor| Some(attempt(cursor, &mut p))
+}
+
+/// A parser that matches `a`, or `b` if `a` doesn't match
+pub fn alt<'a, T, O>(
+    mut a: impl FnMut(&mut Cursor<'a, T>) -> Option<O>,
+    mut b: impl FnMut(&mut Cursor<'a, T>) -> Option<O>,
+) -> impl FnMut(&mut Cursor<'a, T>) -> Option<O> {
+    move |cursor| attempt(cursor, &mut a).or_else(|| attempt(cursor, &mut b))
+}
+
+/// A parser that matches `p` zero or more times
+///
+/// This always matches. It stops at the first time `p` doesn't match, or
+/// matches without consuming anything
+pub fn many<'a, T, O>(
+    mut p: impl FnMut(&mut Cursor<'a, T>) -> Option<O>,
+) -> impl FnMut(&mut Cursor<'a, T>) -> Option<Vec<O>> {
+    move |cursor| {
+        let mut out = Vec::new();
+        loop {
+            let position = cursor.position();
+            match attempt(cursor, &mut p) {
+                Some(item) if cursor.position() != position => out.push(item),
+                _ => return Some(out),
+            }
+        }
+    }
+}
+
+/// A parser that matches `p` between `open` and `close`, producing what `p` did
+pub fn delimited<'a, T, A, O, B>(
+    mut open: impl FnMut(&mut Cursor<'a, T>) -> Option<A>,
+    mut p: impl FnMut(&mut Cursor<'a, T>) -> Option<O>,
+    mut close: impl FnMut(&mut Cursor<'a, T>) -> Option<B>,
+) -> impl FnMut(&mut Cursor<'a, T>) -> Option<O> {
+    move |cursor| {
+        attempt(cursor, |cursor| {
+            open(cursor)?;
+            let out = p(cursor)?;
+            close(cursor)?;
+            Some(out)
+        })
+    }
+}
+
+/// A parser that matches `p`, wrapping what it produced with the span of the
+/// tokens it consumed
+///
+/// If `p` matched without consuming anything, the span is empty at the next token
+pub fn spanned<'a, T, O>(
+    mut p: impl FnMut(&mut Cursor<'a, T>) -> Option<O>,
+) -> impl FnMut(&mut Cursor<'a, T>) -> Option<WithSpan<O>>
+where
+    T: Clone,
+{
+    move |cursor| {
+        let start = cursor.position_span().start;
+        let tokens = cursor.remaining();
+        let position = cursor.position();
+        let out = attempt(cursor, &mut p)?;
+        let span = match &tokens[..cursor.position() - position] {
+            [] => Span::from(start..start),
+            [first, .., last] => first.span.join(last.span),
+            [only] => only.span,
+        };
+        Some(WithSpan::new(out, span))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TokenStream;
+
+    fn stream(source: &str) -> TokenStream<char> {
+        crate::testing::chars(source)
+            .into_iter()
+            .filter(|k| !k.item.is_whitespace())
+            .collect()
+    }
+
+    #[test]
+    fn rollback() {
+        let stream = stream("a b c");
+        let mut cursor = stream.cursor();
+
+        let mut ab = spanned(|cursor: &mut Cursor<'_, char>| {
+            token('a')(cursor)?;
+            token('b')(cursor).map(|k| k.item)
+        });
+        let mut ac = |cursor: &mut Cursor<'_, char>| {
+            token('a')(cursor)?;
+            token('c')(cursor).map(|k| k.item)
+        };
+        assert_eq!(attempt(&mut cursor, &mut ac), None);
+        assert_eq!(cursor.position(), 0);
+
+        let mut x = |cursor: &mut Cursor<'_, char>| token('x')(cursor).map(|k| k.item);
+        assert_eq!(alt(&mut ac, &mut x)(&mut cursor), None);
+        assert_eq!(cursor.position(), 0);
+
+        assert_eq!(ab(&mut cursor).map(|k| k.span), Some(Span::from(0..3)));
+        assert_eq!(cursor.position(), 2);
+    }
+
+    #[test]
+    fn many_and_spans() {
+        let stream = stream("a a a b");
+        let mut cursor = stream.cursor();
+
+        let words = spanned(many(token('a')))(&mut cursor).unwrap();
+        assert_eq!(words.item.len(), 3);
+        assert_eq!(words.span, Span::from(0..5));
+
+        // matches nothing, so the span is empty at the next token
+        let none = spanned(many(token('a')))(&mut cursor).unwrap();
+        assert!(none.item.is_empty());
+        assert_eq!(none.span, Span::from(6..6));
+
+        // a parser that doesn't consume stops `many`
+        assert_eq!(many(opt(token('a')))(&mut cursor), Some(vec![]));
+
+        assert!(token('b')(&mut cursor).is_some());
+        assert_eq!(
+            spanned(opt(token('c')))(&mut cursor).map(|k| k.span),
+            Some(Span::from(7..7))
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{TokenFilter, WithSpan};
+use alloc::vec::Vec;
+
+/// `Region` is what a token is, to [`Conditionals`](./struct.Conditionals.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Region {
+    /// A begin, else or end token
+    Directive,
+    /// A token in an active region
+    Active,
+    /// A token in an inactive region
+    Inactive,
+}
+
+/// `Conditionals` tracks conditional regions, like `#if`/`#else`/`#endif`
+///
+/// When a `begin` token is found, `eval` is given its slice of the source to
+/// decide whether the region is active. Regions can nest, and the conditions
+/// inside of an inactive region aren't evaluated.
+///
+/// As a [`TokenFilter`](./trait.TokenFilter.html), this drops the directives and
+/// the tokens in inactive regions. To mark them instead, use
+/// [`classify`](#method.classify)
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, Conditionals, Pipeline};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "#if [a-z]+"]
+///     If,
+///     #[token = "#else"]
+///     Else,
+///     #[token = "#endif"]
+///     EndIf,
+///     #[regex = "[a-z]+"]
+///     Word,
+/// }
+///
+/// let source = "a #if debug b #else c #endif d";
+/// let conditionals = Conditionals::new(source, Token::If, Token::EndIf, |s| s == "#if debug")
+///     .with_else(Token::Else);
+///
+/// let words = Pipeline::new()
+///     .then(conditionals)
+///     .run(lex::<Token, _>(source).spanned())
+///     .map(|k| &source[k.span])
+///     .collect::<Vec<_>>();
+/// assert_eq!(words, vec!["a", "b", "d"]);
+/// ```
+pub struct Conditionals<'a, T, F> {
+    source: &'a str,
+    begin: T,
+    otherwise: Option<T>,
+    end: T,
+    eval: F,
+    // (whether the enclosing region is active, whether this branch is taken)
+    stack: Vec<(bool, bool)>,
+}
+
+impl<'a, T, F> Conditionals<'a, T, F>
+where
+    T: PartialEq,
+    F: FnMut(&str) -> bool,
+{
+    /// Track regions starting with `begin` and ending with `end`, for tokens from `source`
+    pub fn new(source: &'a str, begin: T, end: T, eval: F) -> Self {
+        Self {
+            source,
+            begin,
+            otherwise: None,
+            end,
+            eval,
+            stack: vec![],
+        }
+    }
+
+    /// Use `otherwise` to switch to the other branch of a region
+    pub fn with_else(mut self, otherwise: T) -> Self {
+        self.otherwise.replace(otherwise);
+        self
+    }
+
+    /// Whether the current region is active
+    pub fn is_active(&self) -> bool {
+        match self.stack.last() {
+            Some(&(outer, taken)) => outer && taken,
+            None => true,
+        }
+    }
+
+    /// How many regions are open
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Classify the next `token`, updating the regions
+    ///
+    /// Unmatched else and end tokens are treated as directives
+    pub fn classify(&mut self, token: &WithSpan<T>) -> Region {
+        if token.item == self.begin {
+            let outer = self.is_active();
+            let taken = outer
+                && self
+                    .source
+                    .get(token.span.start..token.span.end)
+                    .is_some_and(|s| (self.eval)(s));
+            self.stack.push((outer, taken));
+            return Region::Directive;
+        }
+
+        if self.otherwise.as_ref() == Some(&token.item) {
+            if let Some((_, taken)) = self.stack.last_mut() {
+                *taken = !*taken;
+            }
+            return Region::Directive;
+        }
+
+        if token.item == self.end {
+            self.stack.pop();
+            return Region::Directive;
+        }
+
+        if self.is_active() {
+            Region::Active
+        } else {
+            Region::Inactive
+        }
+    }
+}
+
+impl<T, F> TokenFilter<T> for Conditionals<'_, T, F>
+where
+    T: PartialEq,
+    F: FnMut(&str) -> bool,
+{
+    fn push(&mut self, token: WithSpan<T>, out: &mut Vec<WithSpan<T>>) {
+        if self.classify(&token) == Region::Active {
+            out.push(token)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "#if [a-z]+"]
+        If,
+        #[token = "#else"]
+        Else,
+        #[token = "#endif"]
+        EndIf,
+        #[regex = "[a-z]+"]
+        Word,
+    }
+
+    #[test]
+    fn classify() {
+        let source = "a #if no b #if yes c #endif #else d #if yes e #else f #endif #endif g";
+        let mut evaluated = vec![];
+        let mut conditionals = Conditionals::new(source, Token::If, Token::EndIf, |s| {
+            evaluated.push(s.to_string());
+            s.ends_with("yes")
+        })
+        .with_else(Token::Else);
+
+        let mut depths = vec![];
+        let regions = crate::lex::<Token, _>(source)
+            .spanned()
+            .filter_map(|k| {
+                let region = conditionals.classify(&k);
+                depths.push(conditionals.depth());
+                Some((&source[k.span], region)).filter(|_| region != Region::Directive)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            regions,
+            vec![
+                ("a", Region::Active),
+                ("b", Region::Inactive),
+                ("c", Region::Inactive),
+                ("d", Region::Active),
+                ("e", Region::Active),
+                ("f", Region::Inactive),
+                ("g", Region::Active),
+            ]
+        );
+        assert_eq!(depths.iter().max(), Some(&2));
+        assert_eq!(depths.last(), Some(&0));
+
+        drop(conditionals);
+        assert_eq!(evaluated, vec!["#if no", "#if yes"]);
+    }
+}
@@ -0,0 +1,295 @@
+use crate::{Expected, Span, TokenSet, TokenStream, WithSpan};
+
+/// `Cursor` is a position in a slice of spanned tokens, for writing parsers
+///
+/// This is created with [`TokenStream::cursor`](./struct.TokenStream.html#method.cursor)
+/// or [`Cursor::new`](#method.new)
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, TokenSet, TokenStream, Span};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Ident,
+///     #[token = "="]
+///     Equal,
+///     #[token = ";"]
+///     Semi,
+/// }
+///
+/// let stream = lex::<Token, _>("a = = b; c").spanned().collect::<TokenStream<_>>();
+/// let mut cursor = stream.cursor();
+///
+/// assert!(cursor.eat(Token::Ident).is_some());
+/// assert!(cursor.eat(Token::Equal).is_some());
+/// assert!(cursor.eat(Token::Ident).is_none());
+///
+/// let skipped = cursor.recover_to(&TokenSet::new([Token::Semi]));
+/// assert_eq!(skipped, Some(Span::from(4..7)));
+/// assert!(cursor.at(Token::Semi));
+/// ```
+#[derive(Debug)]
+pub struct Cursor<'a, T> {
+    tokens: &'a [WithSpan<T>],
+    pos: usize,
+}
+
+impl<T> Clone for Cursor<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Cursor<'_, T> {}
+
+impl<'a, T> Cursor<'a, T> {
+    /// Create a cursor at the start of the `tokens`
+    pub fn new(tokens: &'a [WithSpan<T>]) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    /// The index of the next token
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Move the cursor back (or forward) to a previous `position`
+    pub fn reset(&mut self, position: usize) {
+        self.pos = position.min(self.tokens.len())
+    }
+
+    /// Whether all of the tokens have been consumed
+    pub fn is_at_end(&self) -> bool {
+        self.pos == self.tokens.len()
+    }
+
+    /// The tokens that haven't been consumed
+    pub fn remaining(&self) -> &'a [WithSpan<T>] {
+        &self.tokens[self.pos..]
+    }
+
+    /// Look at the next token, without consuming it
+    pub fn peek(&self) -> Option<&'a WithSpan<T>> {
+        self.peek_nth(0)
+    }
+
+    /// Look at the token `n` tokens ahead, without consuming it
+    pub fn peek_nth(&self, n: usize) -> Option<&'a WithSpan<T>> {
+        self.tokens.get(self.pos + n)
+    }
+
+    /// Consume the next token
+    pub fn bump(&mut self) -> Option<&'a WithSpan<T>> {
+        let token = self.peek()?;
+        self.pos += 1;
+        Some(token)
+    }
+
+    /// Whether the next token is a `kind`
+    pub fn at(&self, kind: T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.peek().is_some_and(|k| k.item == kind)
+    }
+
+    /// Whether the next token is one of the kinds in `set`
+    pub fn at_any(&self, set: &TokenSet<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        self.peek().is_some_and(|k| set.contains(&k.item))
+    }
+
+    /// Consume the next token if it is a `kind`
+    pub fn eat(&mut self, kind: T) -> Option<&'a WithSpan<T>>
+    where
+        T: PartialEq,
+    {
+        if self.at(kind) {
+            return self.bump();
+        }
+        None
+    }
+
+    /// Consume the next token if it is a `kind`, or produce an error
+    pub fn expect(&mut self, kind: T) -> Result<&'a WithSpan<T>, Expected<T>>
+    where
+        T: PartialEq + Clone,
+    {
+        self.expect_any(&TokenSet::new(Some(kind)))
+    }
+
+    /// Consume the next token if it is one of the kinds in `set`, or produce an error
+    ///
+    /// The error is displayed as a message like "expected `,` or `)`, found `;`",
+    /// using the [`TokenName`](./trait.TokenName.html)s of the kinds
+    pub fn expect_any(&mut self, set: &TokenSet<T>) -> Result<&'a WithSpan<T>, Expected<T>>
+    where
+        T: PartialEq + Clone,
+    {
+        if self.at_any(set) {
+            return Ok(self.bump().expect("next token"));
+        }
+        Err(Expected {
+            expected: set.clone(),
+            found: self.peek().cloned(),
+        })
+    }
+
+    /// Skip tokens until one of the kinds in `sync` is next, or the end is reached
+    ///
+    /// The synchronization token isn't consumed. This returns the span covering
+    /// the skipped tokens, if any were skipped
+    pub fn recover_to(&mut self, sync: &TokenSet<T>) -> Option<Span>
+    where
+        T: PartialEq,
+    {
+        let mut skipped: Option<Span> = None;
+        while !self.is_at_end() && !self.at_any(sync) {
+            let span = self.bump()?.span;
+            skipped = Some(skipped.map_or(span, |s| s.join(span)));
+        }
+        skipped
+    }
+}
+
+impl<T> crate::TokenInput for Cursor<'_, T>
+where
+    T: Clone,
+{
+    type Token = T;
+
+    fn peek(&self) -> Option<WithSpan<&Self::Token>> {
+        Cursor::peek(self).map(WithSpan::as_ref)
+    }
+
+    fn advance(&mut self) -> Option<WithSpan<Self::Token>> {
+        self.bump().cloned()
+    }
+
+    fn position_span(&self) -> Span {
+        match (Cursor::peek(self), self.tokens.last()) {
+            (Some(token), _) => token.span,
+            (None, Some(last)) => Span::from(last.span.end..last.span.end),
+            (None, None) => Span::from(0..0),
+        }
+    }
+}
+
+impl<T> TokenStream<T> {
+    /// Create a [`Cursor`](./struct.Cursor.html) at the start of the stream
+    pub fn cursor(&self) -> Cursor<'_, T> {
+        Cursor::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TokenName;
+    use logos::Logos;
+
+    #[derive(Logos, TokenName, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        #[name("identifier")]
+        Ident,
+        #[token = ";"]
+        #[name("`;`")]
+        Semi,
+        #[token = "}"]
+        #[name("`}`")]
+        Close,
+    }
+
+    #[test]
+    fn expect_any() {
+        let stream = crate::lex::<Token, _>("a ;")
+            .spanned()
+            .collect::<TokenStream<_>>();
+        let mut cursor = stream.cursor();
+
+        let set = TokenSet::new([Token::Ident, Token::Close]);
+        assert_eq!(
+            cursor.expect_any(&set).map(|k| k.span),
+            Ok(Span::from(0..1))
+        );
+
+        let err = cursor.expect_any(&set).unwrap_err();
+        assert_eq!(
+            err.found,
+            Some(WithSpan::new(Token::Semi, Span::from(2..3)))
+        );
+        assert_eq!(err.to_string(), "expected identifier or `}`, found `;`");
+        assert_eq!(cursor.position(), 1);
+
+        assert!(cursor.expect(Token::Semi).is_ok());
+        assert_eq!(
+            cursor.expect(Token::Close).unwrap_err().to_string(),
+            "expected `}`, found end of input"
+        );
+    }
+
+    #[test]
+    fn recover_to() {
+        let stream = crate::lex::<Token, _>("a b ; } c d")
+            .spanned()
+            .collect::<TokenStream<_>>();
+        let sync = TokenSet::new([Token::Semi, Token::Close]);
+
+        let mut cursor = stream.cursor();
+        assert_eq!(cursor.recover_to(&sync), Some(Span::from(0..3)));
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor.recover_to(&sync), None);
+
+        cursor.bump();
+        assert_eq!(cursor.recover_to(&sync), None);
+        assert!(cursor.eat(Token::Close).is_some());
+
+        assert_eq!(cursor.recover_to(&sync), Some(Span::from(8..11)));
+        assert!(cursor.is_at_end());
+        assert_eq!(cursor.recover_to(&sync), None);
+
+        cursor.reset(1);
+        assert_eq!(cursor.peek().map(|k| k.span), Some(Span::from(2..3)));
+        assert_eq!(cursor.remaining().len(), 5);
+    }
+
+    #[test]
+    fn token_input() {
+        use crate::TokenInput;
+
+        let source = "a ; b $$";
+        let stream = crate::lex::<Token, _>(source)
+            .spanned()
+            .collect::<TokenStream<_>>();
+        let mut cursor = stream.cursor();
+        let mut lexer = crate::lex::<Token, _>(source).spanned();
+
+        loop {
+            assert_eq!(cursor.position_span(), lexer.position_span());
+            assert_eq!(cursor.is_at_end(), lexer.is_at_end());
+            assert_eq!(TokenInput::peek(&cursor), TokenInput::peek(&lexer));
+            let next = TokenInput::advance(&mut cursor);
+            assert_eq!(next, TokenInput::advance(&mut lexer));
+            if next.is_none() {
+                break;
+            }
+        }
+        assert_eq!(cursor.position_span(), Span::from(8..8));
+        assert_eq!(
+            TokenStream::<Token>::default().cursor().position_span(),
+            Span::from(0..0)
+        );
+    }
+}
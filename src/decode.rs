@@ -0,0 +1,267 @@
+use crate::{Span, SpannedLexer, WithSpan};
+use alloc::{string::String, vec::Vec};
+
+/// An encoding that a [`DecodedSource`](./struct.DecodedSource.html) can decode from
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Encoding {
+    /// ISO-8859-1, each byte is the `char` of the same value
+    Latin1,
+    /// Windows-1252, Latin-1 with printable chars in `0x80..=0x9F`
+    Windows1252,
+    /// UTF-16, little endian
+    Utf16Le,
+    /// UTF-16, big endian
+    Utf16Be,
+}
+
+// 0x80..=0x9F for Windows-1252, the undefined bytes map to the C1 control chars
+const WINDOWS_1252: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// `DecodedSource` is a non UTF-8 source that was transcoded to UTF-8 so it can be lexed
+///
+/// Spans into the decoded text can be mapped back to the byte offsets of the
+/// original source with [`original_span`](#method.original_span)
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedSource {
+    text: String,
+    // (decoded, original) offsets, bytes between these are 1:1
+    map: Vec<(usize, usize)>,
+    len: usize,
+}
+
+impl DecodedSource {
+    /// Decode `bytes` from the `encoding` into UTF-8
+    ///
+    /// Invalid UTF-16 is replaced with `U+FFFD`
+    pub fn decode(bytes: &[u8], encoding: Encoding) -> Self {
+        let mut this = Self {
+            text: String::with_capacity(bytes.len()),
+            map: vec![(0, 0)],
+            len: bytes.len(),
+        };
+
+        match encoding {
+            Encoding::Latin1 => {
+                for (i, &b) in bytes.iter().enumerate() {
+                    this.push(i, 1, b as char)
+                }
+            }
+            Encoding::Windows1252 => {
+                for (i, &b) in bytes.iter().enumerate() {
+                    let c = match b {
+                        0x80..=0x9F => WINDOWS_1252[(b - 0x80) as usize],
+                        _ => b as char,
+                    };
+                    this.push(i, 1, c)
+                }
+            }
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                let unit = |c: &[u8]| match encoding {
+                    Encoding::Utf16Le => u16::from_le_bytes([c[0], c[1]]),
+                    _ => u16::from_be_bytes([c[0], c[1]]),
+                };
+                let offset = this.push_utf16(bytes.chunks_exact(2).map(unit), 2);
+                if bytes.len() % 2 == 1 {
+                    this.push(offset, 1, core::char::REPLACEMENT_CHARACTER)
+                }
+            }
+        }
+
+        this
+    }
+
+    /// Decode UTF-16 code `units` into UTF-8
+    ///
+    /// The original offsets for this are in code units, rather than bytes. Invalid
+    /// UTF-16 is replaced with `U+FFFD`
+    pub fn from_utf16(units: &[u16]) -> Self {
+        let mut this = Self {
+            text: String::with_capacity(units.len()),
+            map: vec![(0, 0)],
+            len: units.len(),
+        };
+        this.push_utf16(units.iter().copied(), 1);
+        this
+    }
+
+    fn push_utf16(&mut self, units: impl Iterator<Item = u16>, size: usize) -> usize {
+        let mut offset = 0;
+        for c in core::char::decode_utf16(units) {
+            let (c, len) = match c {
+                Ok(c) => (c, c.len_utf16() * size),
+                Err(..) => (core::char::REPLACEMENT_CHARACTER, size),
+            };
+            self.push(offset, len, c);
+            offset += len;
+        }
+        offset
+    }
+
+    fn push(&mut self, original: usize, len: usize, c: char) {
+        let start = self.text.len();
+        self.text.push(c);
+        if c.len_utf8() == len {
+            return;
+        }
+        if self.map.last() != Some(&(start, original)) {
+            self.map.push((start, original))
+        }
+        self.map.push((self.text.len(), original + len))
+    }
+
+    /// The decoded UTF-8 text
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Map a byte offset in the decoded text to the byte offset in the original source
+    pub fn original_offset(&self, offset: usize) -> usize {
+        let n = self.map.partition_point(|&(decoded, _)| decoded <= offset);
+        let (decoded, original) = self.map[n - 1];
+        (original + (offset - decoded)).min(self.len)
+    }
+
+    /// Map a [`Span`](./struct.Span.html) in the decoded text to the span in the
+    /// original source
+    pub fn original_span(&self, span: Span) -> Span {
+        Span {
+            start: self.original_offset(span.start),
+            end: self.original_offset(span.end),
+        }
+    }
+}
+
+impl DecodedSource {
+    /// Lex the decoded text, yielding the tokens wrapped with their
+    /// [`Span`](./struct.Span.html) in the original source
+    pub fn spanned<'a, T>(&'a self) -> DecodedLexer<'a, T>
+    where
+        T: PartialEq<T> + ::logos::Logos + ::logos::source::WithSource<&'a str>,
+    {
+        DecodedLexer {
+            source: self,
+            lexer: SpannedLexer::new(self.as_str()),
+        }
+    }
+}
+
+/// A lexer over a [`DecodedSource`](./struct.DecodedSource.html) that yields
+/// tokens wrapped with their [`Span`](./struct.Span.html) in the original source
+///
+/// This is created with [`DecodedSource::spanned`](./struct.DecodedSource.html#method.spanned)
+pub struct DecodedLexer<'a, T>
+where
+    T: PartialEq<T> + ::logos::Logos,
+{
+    source: &'a DecodedSource,
+    lexer: SpannedLexer<T, &'a str>,
+}
+
+impl<'a, T> Iterator for DecodedLexer<'a, T>
+where
+    T: Clone + PartialEq<T>,
+    T: ::logos::Logos + ::logos::source::WithSource<&'a str>,
+{
+    type Item = WithSpan<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let WithSpan { item, span } = self.lexer.next()?;
+        Some(WithSpan::new(item, self.source.original_span(span)))
+    }
+}
+
+impl AsRef<str> for DecodedSource {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+        #[token = "€"]
+        Euro,
+        #[token = "é"]
+        Acute,
+        #[token = "😀"]
+        Grin,
+    }
+
+    fn lex(source: &DecodedSource) -> Vec<(Token, Span)> {
+        crate::lex::<Token, _>(source.as_str())
+            .spanned()
+            .map(|k| (k.item, source.original_span(k.span)))
+            .collect()
+    }
+
+    #[test]
+    fn decode() {
+        let latin1 = DecodedSource::decode(b"ab \xE9 cd", Encoding::Latin1);
+        assert_eq!(latin1.as_str(), "ab é cd");
+        assert_eq!(
+            lex(&latin1),
+            vec![
+                (Token::Word, Span { start: 0, end: 2 }),
+                (Token::Acute, Span { start: 3, end: 4 }),
+                (Token::Word, Span { start: 5, end: 7 }),
+            ]
+        );
+
+        let cp1252 = DecodedSource::decode(b"\x80ab", Encoding::Windows1252);
+        assert_eq!(cp1252.as_str(), "€ab");
+        assert_eq!(
+            lex(&cp1252),
+            vec![
+                (Token::Euro, Span { start: 0, end: 1 }),
+                (Token::Word, Span { start: 1, end: 3 }),
+            ]
+        );
+
+        let utf16 = "ab 😀 €cd".encode_utf16().flat_map(u16::to_be_bytes);
+        let utf16 = DecodedSource::decode(&utf16.collect::<Vec<_>>(), Encoding::Utf16Be);
+        assert_eq!(utf16.as_str(), "ab 😀 €cd");
+        assert_eq!(
+            lex(&utf16),
+            vec![
+                (Token::Word, Span { start: 0, end: 4 }),
+                (Token::Grin, Span { start: 6, end: 10 }),
+                (Token::Euro, Span { start: 12, end: 14 }),
+                (Token::Word, Span { start: 14, end: 18 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn utf16_units() {
+        let units = "ab 😀 €cd".encode_utf16().collect::<Vec<_>>();
+        let source = DecodedSource::from_utf16(&units);
+        let tokens = source
+            .spanned::<Token>()
+            .map(|k| (k.item, k.span))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::Word, Span { start: 0, end: 2 }),
+                (Token::Grin, Span { start: 3, end: 5 }),
+                (Token::Euro, Span { start: 6, end: 7 }),
+                (Token::Word, Span { start: 7, end: 9 }),
+            ]
+        );
+        assert_eq!(String::from_utf16(&units[7..9]).unwrap(), "cd");
+    }
+}
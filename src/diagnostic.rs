@@ -0,0 +1,448 @@
+use crate::export::json_string;
+use crate::{excerpt, ColumnMode, EditError, LineIndex, Span, TextEdits};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// `Severity` is how serious a [`Diagnostic`](./struct.Diagnostic.html) is
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Note,
+    Warning,
+    Error,
+}
+
+impl core::fmt::Display for Severity {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Note => "note",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// `Diagnostic` is a message about a [`Span`](./struct.Span.html) of a source
+///
+/// It can have a code (like `E0001`), and notes and help text after the excerpt
+///
+/// ```rust
+/// # use logos_iterator::{Diagnostic, Span};
+/// let source = "let a = 1;\nlet b = ;\n";
+/// let diagnostic = Diagnostic::error("expected an expression", Span::from(19..20))
+///     .with_code("E0001")
+///     .with_note("`let` needs a value")
+///     .with_help("add a value, like `let b = 2;`");
+///
+/// assert_eq!(
+///     diagnostic.display(source).to_string(),
+///     "error[E0001]: expected an expression\n \
+///      --> 2:9\n\
+///      2 | let b = ;\n  \
+///        |         ^\n  \
+///        = note: `let` needs a value\n  \
+///        = help: add a value, like `let b = 2;`\n"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    /// An identifier for this kind of diagnostic, like `E0001`
+    pub code: Option<String>,
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+    /// Fixes, shown after the help
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Diagnostic {
+    /// Create a diagnostic with the `message` about `span`
+    pub fn new(severity: Severity, message: impl ToString, span: Span) -> Self {
+        Self {
+            severity,
+            message: message.to_string(),
+            span,
+            code: None,
+            notes: vec![],
+            help: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Create an error with the `message` about `span`
+    pub fn error(message: impl ToString, span: Span) -> Self {
+        Self::new(Severity::Error, message, span)
+    }
+
+    /// Create a warning with the `message` about `span`
+    pub fn warning(message: impl ToString, span: Span) -> Self {
+        Self::new(Severity::Warning, message, span)
+    }
+
+    /// Set the code of this diagnostic
+    pub fn with_code(mut self, code: impl ToString) -> Self {
+        self.code.replace(code.to_string());
+        self
+    }
+
+    /// Add a note, shown after the excerpt
+    pub fn with_note(mut self, note: impl ToString) -> Self {
+        self.notes.push(note.to_string());
+        self
+    }
+
+    /// Add a help message, shown after the notes
+    pub fn with_help(mut self, help: impl ToString) -> Self {
+        self.help.push(help.to_string());
+        self
+    }
+
+    /// Add a suggested fix, shown after the help
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+
+    /// Convert this into a JSON object in the format of rustc's
+    /// `--error-format=json` diagnostics, for the file `file_name` with `source`
+    ///
+    /// Lines and columns are 1-based, with columns counted in `char`s like rustc.
+    /// The notes, help and suggestions are children of the diagnostic, and `rendered` is the
+    /// text from [`display`](#method.display)
+    ///
+    /// ```rust
+    /// # use logos_iterator::{Diagnostic, Span};
+    /// let json = Diagnostic::error("unknown token", Span::from(2..3))
+    ///     .with_code("E0001")
+    ///     .to_rustc_json("main.txt", "a $ b");
+    /// assert!(json.starts_with(r#"{"$message_type":"diagnostic","message":"unknown token","code":{"code":"E0001","explanation":null},"level":"error","spans":[{"file_name":"main.txt","byte_start":2,"byte_end":3,"line_start":1,"line_end":1,"column_start":3,"column_end":4,"#));
+    /// ```
+    pub fn to_rustc_json(&self, file_name: &str, source: &str) -> String {
+        use core::fmt::Write as _;
+
+        let index = LineIndex::new(source).with_columns(ColumnMode::Chars);
+        let code = match &self.code {
+            Some(code) => format!(r#"{{"code":{},"explanation":null}}"#, json_string(code)),
+            None => String::from("null"),
+        };
+
+        let mut children = String::new();
+        let notes = self.notes.iter().map(|s| ("note", s, None));
+        let help = self.help.iter().map(|s| ("help", s, None));
+        let suggestions = self
+            .suggestions
+            .iter()
+            .map(|s| ("help", &s.message, Some(s)));
+        for (i, (level, message, suggestion)) in notes.chain(help).chain(suggestions).enumerate() {
+            let spans = suggestion.map_or_else(String::new, |s| {
+                rustc_span(file_name, source, &index, s.span, Some(s))
+            });
+            let _ = write!(
+                children,
+                r#"{}{{"message":{},"code":null,"level":"{}","spans":[{}],"children":[],"rendered":null}}"#,
+                if i > 0 { "," } else { "" },
+                json_string(message),
+                level,
+                spans
+            );
+        }
+
+        format!(
+            concat!(
+                r#"{{"$message_type":"diagnostic","message":{},"code":{},"level":"{}","#,
+                r#""spans":[{}],"children":[{}],"rendered":{}}}"#
+            ),
+            json_string(&self.message),
+            code,
+            self.severity,
+            rustc_span(file_name, source, &index, self.span, None),
+            children,
+            json_string(&self.display(source).to_string())
+        )
+    }
+
+    // the notes, help and suggestions, as they are displayed
+    pub(crate) fn children(&self) -> impl Iterator<Item = (&'static str, String)> + '_ {
+        let notes = self.notes.iter().map(|s| ("note", s.clone()));
+        let help = self.help.iter().map(|s| ("help", s.clone()));
+        let suggestions = self.suggestions.iter().map(|s| ("help", s.to_string()));
+        notes.chain(help).chain(suggestions)
+    }
+
+    /// Display this diagnostic with an excerpt of the `source`
+    pub fn display<'a>(&'a self, source: &'a str) -> DiagnosticDisplay<'a> {
+        DiagnosticDisplay {
+            diagnostic: self,
+            source,
+        }
+    }
+}
+
+/// Displays a [`Diagnostic`](./struct.Diagnostic.html) with an excerpt of its source
+///
+/// This is created with [`Diagnostic::display`](./struct.Diagnostic.html#method.display)
+#[derive(Copy, Clone, Debug)]
+pub struct DiagnosticDisplay<'a> {
+    diagnostic: &'a Diagnostic,
+    source: &'a str,
+}
+
+impl core::fmt::Display for DiagnosticDisplay<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let Diagnostic {
+            severity,
+            message,
+            span,
+            code,
+            ..
+        } = self.diagnostic;
+
+        match code {
+            Some(code) => writeln!(f, "{}[{}]: {}", severity, code, message)?,
+            None => writeln!(f, "{}: {}", severity, message)?,
+        }
+
+        let excerpt = excerpt(self.source, *span, 0);
+        let width = excerpt
+            .lines
+            .last()
+            .map_or(0, |line| line.number.to_string().len());
+
+        let pos = LineIndex::new(self.source).line_col(span.start);
+        writeln!(
+            f,
+            "{:>width$}--> {}:{}",
+            "",
+            pos.line,
+            pos.column,
+            width = width
+        )?;
+        write!(f, "{}", excerpt)?;
+
+        for (kind, text) in self.diagnostic.children() {
+            writeln!(f, "{:>width$} = {}: {}", "", kind, text, width = width)?;
+        }
+        Ok(())
+    }
+}
+
+fn rustc_span(
+    file_name: &str,
+    source: &str,
+    index: &LineIndex,
+    span: Span,
+    suggestion: Option<&Suggestion>,
+) -> String {
+    use core::fmt::Write as _;
+
+    let mut text = String::new();
+    for (i, line) in excerpt(source, span, 0).lines.iter().enumerate() {
+        let highlight = line.highlight.unwrap_or(Span::from(0..0));
+        let column = |offset: usize| {
+            line.text
+                .get(..offset)
+                .map_or(offset, |s| s.chars().count())
+                + 1
+        };
+        let _ = write!(
+            text,
+            r#"{}{{"text":{},"highlight_start":{},"highlight_end":{}}}"#,
+            if i > 0 { "," } else { "" },
+            json_string(line.text),
+            column(highlight.start),
+            column(highlight.end)
+        );
+    }
+
+    let (replacement, applicability) = match suggestion {
+        Some(s) => (
+            json_string(&s.replacement),
+            format!(r#""{:?}""#, s.applicability),
+        ),
+        None => (String::from("null"), String::from("null")),
+    };
+
+    let (start, end) = (index.line_col(span.start), index.line_col(span.end));
+    format!(
+        concat!(
+            r#"{{"file_name":{},"byte_start":{},"byte_end":{},"#,
+            r#""line_start":{},"line_end":{},"column_start":{},"column_end":{},"#,
+            r#""is_primary":true,"text":[{}],"label":null,"suggested_replacement":{},"#,
+            r#""suggestion_applicability":{},"expansion":null}}"#
+        ),
+        json_string(file_name),
+        span.start,
+        span.end,
+        start.line,
+        end.line,
+        start.column,
+        end.column,
+        text,
+        replacement,
+        applicability
+    )
+}
+
+/// `Applicability` is how confident a [`Suggestion`](./struct.Suggestion.html) is
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Applicability {
+    /// The suggestion is definitely what was meant, and can be applied automatically
+    MachineApplicable,
+    /// The suggestion might not be what was meant, so it should only be shown
+    MaybeIncorrect,
+}
+
+/// `Suggestion` is a fix for a [`Diagnostic`](./struct.Diagnostic.html), that
+/// replaces the text at a [`Span`](./struct.Span.html)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    pub span: Span,
+    pub replacement: String,
+    pub applicability: Applicability,
+    /// What the suggestion does, like "add a semicolon"
+    pub message: String,
+}
+
+impl Suggestion {
+    /// Create a machine applicable suggestion that replaces the text at `span` with `replacement`
+    pub fn new(span: Span, replacement: impl ToString) -> Self {
+        let replacement = replacement.to_string();
+        let message = if replacement.is_empty() {
+            String::from("remove this")
+        } else if span.start == span.end {
+            format!("insert `{}`", replacement)
+        } else {
+            format!("replace with `{}`", replacement)
+        };
+        Self {
+            span,
+            replacement,
+            applicability: Applicability::MachineApplicable,
+            message,
+        }
+    }
+
+    /// Set how confident this suggestion is
+    pub fn with_applicability(mut self, applicability: Applicability) -> Self {
+        self.applicability = applicability;
+        self
+    }
+
+    /// Set what the suggestion does
+    pub fn with_message(mut self, message: impl ToString) -> Self {
+        self.message = message.to_string();
+        self
+    }
+}
+
+impl core::fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Apply all of the machine applicable [`Suggestion`](./struct.Suggestion.html)s
+/// of the `diagnostics` to `source`
+///
+/// This fails if any of the suggestions overlap
+///
+/// ```rust
+/// # use logos_iterator::{apply_fixes, Applicability, Diagnostic, Span, Suggestion};
+/// let source = "let a = 1\nlet b == 2;";
+/// let diagnostics = vec![
+///     Diagnostic::error("expected `;`", Span::from(9..9))
+///         .with_suggestion(Suggestion::new(Span::from(9..9), ";")),
+///     Diagnostic::error("expected `=`", Span::from(16..18))
+///         .with_suggestion(Suggestion::new(Span::from(16..18), "=")),
+///     Diagnostic::warning("unused", Span::from(4..5)).with_suggestion(
+///         Suggestion::new(Span::from(4..5), "_a").with_applicability(Applicability::MaybeIncorrect),
+///     ),
+/// ];
+///
+/// assert_eq!(apply_fixes(source, &diagnostics).unwrap(), "let a = 1;\nlet b = 2;");
+/// assert!(diagnostics[0].display(source).to_string().ends_with("= help: insert `;`\n"));
+/// ```
+pub fn apply_fixes(source: &str, diagnostics: &[Diagnostic]) -> Result<String, EditError> {
+    let mut edits = TextEdits::new();
+    for suggestion in diagnostics.iter().flat_map(|k| &k.suggestions) {
+        if suggestion.applicability == Applicability::MachineApplicable {
+            edits.replace(suggestion.span, suggestion.replacement.as_str());
+        }
+    }
+    edits.apply(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let source = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj + k";
+        let diagnostic = Diagnostic::warning("unused", Span::from(18..23));
+        assert_eq!(
+            diagnostic.display(source).to_string(),
+            "warning: unused\n  --> 10:1\n10 | j + k\n   | ^^^^^\n"
+        );
+
+        let diagnostic = diagnostic.with_help("remove it").with_note("here");
+        assert!(diagnostic
+            .display(source)
+            .to_string()
+            .ends_with("   = note: here\n   = help: remove it\n"));
+        assert!(Severity::Error > Severity::Warning);
+    }
+
+    #[test]
+    fn rustc_json() {
+        let source = "é\nab \"cd\"";
+        let json = Diagnostic::warning("a \"string\"", Span::from(6..10))
+            .with_note("one")
+            .with_help("two")
+            .to_rustc_json("dir\\file", source);
+
+        let expected = concat!(
+            r#"{"$message_type":"diagnostic","message":"a \"string\"","code":null,"level":"warning","#,
+            r#""spans":[{"file_name":"dir\\file","byte_start":6,"byte_end":10,"#,
+            r#""line_start":2,"line_end":2,"column_start":4,"column_end":8,"is_primary":true,"#,
+            r#""text":[{"text":"ab \"cd\"","highlight_start":4,"highlight_end":8}],"#,
+            r#""label":null,"suggested_replacement":null,"suggestion_applicability":null,"#,
+            r#""expansion":null}],"children":["#,
+            r#"{"message":"one","code":null,"level":"note","spans":[],"children":[],"rendered":null},"#,
+            r#"{"message":"two","code":null,"level":"help","spans":[],"children":[],"rendered":null}],"#,
+        );
+        assert!(json.starts_with(expected), "{}", json);
+        assert!(json.ends_with(r#"= help: two\n"}"#), "{}", json);
+
+        let json = Diagnostic::error("expected `;`", Span::from(2..2))
+            .with_suggestion(Suggestion::new(Span::from(2..2), ";").with_message("add it"))
+            .to_rustc_json("file", "ab");
+        assert!(json.contains(concat!(
+            r#""children":[{"message":"add it","code":null,"level":"help","spans":[{"#,
+            r#""file_name":"file","byte_start":2,"byte_end":2,"#
+        )));
+        assert!(json.contains(
+            r#""suggested_replacement":";","suggestion_applicability":"MachineApplicable""#
+        ));
+    }
+
+    #[test]
+    fn suggestions() {
+        let span = Span::from(1..2);
+        assert_eq!(Suggestion::new(span, "").to_string(), "remove this");
+        assert_eq!(Suggestion::new(span, "x").to_string(), "replace with `x`");
+
+        let overlapping = [
+            Diagnostic::error("a", span).with_suggestion(Suggestion::new(span, "x")),
+            Diagnostic::error("b", span).with_suggestion(Suggestion::new(span, "y")),
+        ];
+        assert!(matches!(
+            apply_fixes("abc", &overlapping),
+            Err(EditError::Overlap { .. })
+        ));
+        assert_eq!(apply_fixes("abc", &overlapping[1..]).unwrap(), "ayc");
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{Span, WithSpan};
+use alloc::{string::String, vec::Vec};
+
+/// `DocBlock` is a run of doc comments, joined into Markdown
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocBlock {
+    /// The comments without their markers, one line per line of comment
+    pub text: String,
+    /// The span of the doc comments
+    pub span: Span,
+    /// The span of the item that follows the doc comments, if any
+    pub item: Option<Span>,
+}
+
+/// Collect the doc comments in `tokens` into [`DocBlock`](./struct.DocBlock.html)s
+///
+/// Doc comments are the tokens where `is_doc` returns `true`, and consecutive
+/// doc comments are joined into a single block. The `///`, `//!`, `/** */` and
+/// `/*! */` markers (and a leading `*` on each line of a block comment) are removed.
+///
+/// The item of a block starts at the next token, and ends at the first token
+/// where `is_item_end` returns `true` that isn't nested in one of the bracket
+/// `pairs`. It also ends before the closer of an enclosing bracket, or the next
+/// doc comment that isn't nested in it
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, extract_docs, Span};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "///[^\n]*\n"]
+///     Doc,
+///     #[regex = "[a-z]+"]
+///     Ident,
+///     #[token = "{"]
+///     Open,
+///     #[token = "}"]
+///     Close,
+///     #[token = ";"]
+///     Semi,
+/// }
+///
+/// let source = "/// A thing\n///\n/// Some *docs*\nstruct thing { a }\nfn f;";
+/// let tokens = lex::<Token, _>(source).spanned().collect::<Vec<_>>();
+/// let docs = extract_docs(
+///     &tokens,
+///     source,
+///     &[(Token::Open, Token::Close)],
+///     |k| *k == Token::Doc,
+///     |k| matches!(k, Token::Semi | Token::Close),
+/// );
+///
+/// assert_eq!(docs.len(), 1);
+/// assert_eq!(docs[0].text, "A thing\n\nSome *docs*");
+/// assert_eq!(&source[docs[0].item.unwrap()], "struct thing { a }");
+/// ```
+pub fn extract_docs<T>(
+    tokens: &[WithSpan<T>],
+    source: &str,
+    pairs: &[(T, T)],
+    is_doc: impl Fn(&T) -> bool,
+    is_item_end: impl Fn(&T) -> bool,
+) -> Vec<DocBlock>
+where
+    T: PartialEq,
+{
+    let mut blocks = vec![];
+    let mut pos = 0;
+    while pos < tokens.len() {
+        if !is_doc(&tokens[pos].item) {
+            pos += 1;
+            continue;
+        }
+
+        let start = pos;
+        while pos < tokens.len() && is_doc(&tokens[pos].item) {
+            pos += 1;
+        }
+        let comments = &tokens[start..pos];
+
+        let lines = comments
+            .iter()
+            .flat_map(|k| strip_markers(source.get(k.span.start..k.span.end).unwrap_or("")))
+            .collect::<Vec<_>>();
+
+        let item = item_span(&tokens[pos..], pairs, &is_doc, &is_item_end);
+        blocks.push(DocBlock {
+            text: lines.join("\n").trim_matches('\n').into(),
+            span: comments[0].span.join(comments[comments.len() - 1].span),
+            item,
+        });
+    }
+    blocks
+}
+
+fn item_span<T>(
+    tokens: &[WithSpan<T>],
+    pairs: &[(T, T)],
+    is_doc: impl Fn(&T) -> bool,
+    is_item_end: impl Fn(&T) -> bool,
+) -> Option<Span>
+where
+    T: PartialEq,
+{
+    let mut depth = 0_usize;
+    let mut item: Option<Span> = None;
+    for token in tokens {
+        if depth == 0 && is_doc(&token.item) {
+            break;
+        }
+        if pairs.iter().any(|(open, _)| *open == token.item) {
+            depth += 1;
+        } else if pairs.iter().any(|(_, close)| *close == token.item) {
+            match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                None => break,
+            }
+        }
+        item = Some(item.map_or(token.span, |s| s.join(token.span)));
+        if depth == 0 && is_item_end(&token.item) {
+            break;
+        }
+    }
+    item
+}
+
+fn strip_markers(comment: &str) -> impl Iterator<Item = &str> {
+    let comment = comment.trim_end();
+    let (body, block) = match comment.get(..3) {
+        Some("///") | Some("//!") => (&comment[3..], false),
+        Some("/**") | Some("/*!") => (comment[3..].trim_end_matches("*/"), true),
+        _ => (comment, false),
+    };
+
+    body.split('\n').map(move |line| {
+        let line = if block {
+            let trimmed = line.trim_start();
+            trimmed.strip_prefix('*').unwrap_or(trimmed)
+        } else {
+            line
+        };
+        line.strip_prefix(' ').unwrap_or(line).trim_end()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "///[^\n]*"]
+        Doc,
+        #[regex = "/\\*\\*([^*]|\\*[^/])*\\*/"]
+        BlockDoc,
+        #[regex = "[a-z]+"]
+        Ident,
+        #[token = "{"]
+        Open,
+        #[token = "}"]
+        Close,
+        #[token = ","]
+        Comma,
+        #[token = "\n"]
+        NewLine,
+    }
+
+    #[test]
+    fn nested() {
+        let source = "/**\n * A struct\n */\nstruct s {\n/// a\na,\n/// b\nb\n}\n/// dangling";
+        let tokens = crate::lex::<Token, _>(source)
+            .spanned()
+            .filter(|k| k.item != Token::NewLine)
+            .collect::<Vec<_>>();
+
+        let docs = extract_docs(
+            &tokens,
+            source,
+            &[(Token::Open, Token::Close)],
+            |k| matches!(k, Token::Doc | Token::BlockDoc),
+            |k| matches!(k, Token::Comma | Token::Close),
+        );
+
+        let docs = docs
+            .iter()
+            .map(|doc| (doc.text.as_str(), doc.item.map(|span| &source[span])))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            docs,
+            vec![
+                ("A struct", Some("struct s {\n/// a\na,\n/// b\nb\n}")),
+                ("a", Some("a,")),
+                ("b", Some("b")),
+                ("dangling", None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use crate::{LineIndex, TokenStream, TokenTree, WithSpan};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Render the `trees` from `source` as S-expressions, one tree per line
+///
+/// Leaves are `(leaf Kind "text")` and groups are `(group "open" ... "close")`,
+/// where the kinds are the `Debug` representation of the tokens. If `spans` is
+/// `true`, the span of each tree follows its text
+///
+/// ```rust
+/// # use logos_iterator::{to_sexp, Span, TokenTree, WithSpan};
+/// let source = "(1)";
+/// let tokens = source
+///     .char_indices()
+///     .map(|(i, c)| WithSpan::new(c, Span::from(i..i + 1)));
+/// let trees = TokenTree::build(tokens, &[('(', ')')]);
+///
+/// assert_eq!(to_sexp(&trees, source, false), r#"(group "(" (leaf '1' "1") ")")"#);
+/// assert_eq!(
+///     to_sexp(&trees, source, true),
+///     r#"(group "(" 0..3 (leaf '1' "1" 1..2) ")")"#
+/// );
+/// ```
+pub fn to_sexp<T>(trees: &[TokenTree<T>], source: &str, spans: bool) -> String
+where
+    T: core::fmt::Debug,
+{
+    let mut out = String::new();
+    for (i, tree) in trees.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        sexp(&mut out, tree, source, spans);
+    }
+    out
+}
+
+fn sexp<T>(out: &mut String, tree: &TokenTree<T>, source: &str, spans: bool)
+where
+    T: core::fmt::Debug,
+{
+    use core::fmt::Write as _;
+    let text = |token: &WithSpan<T>| source.get(token.span.start..token.span.end).unwrap_or("");
+
+    match tree {
+        TokenTree::Leaf(token) => {
+            let _ = write!(out, "(leaf {:?} {:?}", token.item, text(token));
+            if spans {
+                let _ = write!(out, " {}", token.span);
+            }
+        }
+        TokenTree::Group(group) => {
+            let _ = write!(out, "(group {:?}", text(&group.open));
+            if spans {
+                let _ = write!(out, " {}", group.span());
+            }
+            for child in &group.children {
+                out.push(' ');
+                sexp(out, child, source, spans);
+            }
+            if let Some(close) = &group.close {
+                let _ = write!(out, " {:?}", text(close));
+            }
+        }
+    }
+    out.push(')');
+}
+
+/// Render the `trees` from `source` as a Graphviz DOT graph
+///
+/// Each tree is a node labeled with its kind and text, with edges from a
+/// `root` node to the top-level trees and from each group to its children
+///
+/// ```rust
+/// # use logos_iterator::{to_dot, Span, TokenTree, WithSpan};
+/// let source = "(1)";
+/// let tokens = source
+///     .char_indices()
+///     .map(|(i, c)| WithSpan::new(c, Span::from(i..i + 1)));
+/// let trees = TokenTree::build(tokens, &[('(', ')')]);
+///
+/// assert_eq!(
+///     to_dot(&trees, source),
+///     "digraph tokens {\n  \
+///        root [shape=point];\n  \
+///        n0 [label=\"'(' ( )\", shape=box];\n  \
+///        root -> n0;\n  \
+///        n1 [label=\"'1' 1\"];\n  \
+///        n0 -> n1;\n\
+///      }\n"
+/// );
+/// ```
+pub fn to_dot<T>(trees: &[TokenTree<T>], source: &str) -> String
+where
+    T: core::fmt::Debug,
+{
+    fn visit<T>(out: &mut String, tree: &TokenTree<T>, parent: &str, next: &mut usize, source: &str)
+    where
+        T: core::fmt::Debug,
+    {
+        use core::fmt::Write as _;
+        let text = |token: &WithSpan<T>| source.get(token.span.start..token.span.end).unwrap_or("");
+
+        let id = format!("n{}", next);
+        *next += 1;
+
+        match tree {
+            TokenTree::Leaf(token) => {
+                let label = format!("{:?} {}", token.item, text(token));
+                let _ = writeln!(out, "  {} [label=\"{}\"];", id, dot_escape(&label));
+                let _ = writeln!(out, "  {} -> {};", parent, id);
+            }
+            TokenTree::Group(group) => {
+                let mut label = format!("{:?} {}", group.open.item, text(&group.open));
+                if let Some(close) = &group.close {
+                    label.push(' ');
+                    label.push_str(text(close));
+                }
+                let _ = writeln!(
+                    out,
+                    "  {} [label=\"{}\", shape=box];",
+                    id,
+                    dot_escape(&label)
+                );
+                let _ = writeln!(out, "  {} -> {};", parent, id);
+                for child in &group.children {
+                    visit(out, child, &id, next, source);
+                }
+            }
+        }
+    }
+
+    let mut out = String::from("digraph tokens {\n  root [shape=point];\n");
+    let mut next = 0;
+    for tree in trees {
+        visit(&mut out, tree, "root", &mut next, source);
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// `TokenTable` displays tokens as a table with aligned columns
+///
+/// The columns are the index, kind, span, `line:column` and the escaped text.
+/// This is created with [`TokenStream::table`](./struct.TokenStream.html#method.table)
+/// or [`TokenTable::new`](#method.new)
+///
+/// ```rust
+/// # use logos_iterator::{Span, TokenStream, WithSpan};
+/// let source = "a\nbc";
+/// let stream = TokenStream::new(vec![
+///     WithSpan::new('a', Span::from(0..1)),
+///     WithSpan::new('\n', Span::from(1..2)),
+///     WithSpan::new('b', Span::from(2..4)),
+/// ]);
+/// assert_eq!(
+///     stream.table(source).to_string(),
+///     "\
+/// ## | kind | span | pos | text
+/// 0 | 'a'  | 0..1 | 1:1 | \"a\"
+/// 1 | '\\n' | 1..2 | 1:2 | \"\\n\"
+/// 2 | 'b'  | 2..4 | 2:1 | \"bc\"
+/// "
+/// );
+/// ```
+pub struct TokenTable<'a, T> {
+    tokens: &'a [WithSpan<T>],
+    source: &'a str,
+}
+
+impl<'a, T> TokenTable<'a, T> {
+    /// Create a table of the `tokens` from `source`
+    pub fn new(tokens: &'a [WithSpan<T>], source: &'a str) -> Self {
+        Self { tokens, source }
+    }
+}
+
+impl<T> TokenStream<T> {
+    /// Create a [`TokenTable`](./struct.TokenTable.html) for displaying this stream
+    pub fn table<'a>(&'a self, source: &'a str) -> TokenTable<'a, T> {
+        TokenTable::new(self, source)
+    }
+}
+
+impl<T> core::fmt::Display for TokenTable<'_, T>
+where
+    T: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let index = LineIndex::new(self.source);
+        let rows = self
+            .tokens
+            .iter()
+            .enumerate()
+            .map(|(i, token)| {
+                let pos = index.line_col(token.span.start);
+                let text = self
+                    .source
+                    .get(token.span.start..token.span.end)
+                    .unwrap_or("");
+                [
+                    i.to_string(),
+                    format!("{:?}", token.item),
+                    token.span.to_string(),
+                    format!("{}:{}", pos.line, pos.column),
+                    format!("{:?}", text),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let header = ["#", "kind", "span", "pos", "text"];
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut write_row = |row: &[&str]| {
+            for (i, (cell, width)) in row.iter().zip(&widths).enumerate() {
+                match i {
+                    0 => write!(f, "{:>width$}", cell, width = width)?,
+                    4 => write!(f, " | {}", cell)?,
+                    _ => write!(f, " | {:width$}", cell, width = width)?,
+                }
+            }
+            writeln!(f)
+        };
+
+        write_row(&header)?;
+        for row in &rows {
+            write_row(&row.iter().map(String::as_str).collect::<Vec<_>>())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[token = "("]
+        Open,
+        #[token = ")"]
+        Close,
+        #[regex = "[0-9]"]
+        Digit,
+        #[token = "+"]
+        Plus,
+    }
+
+    #[test]
+    fn sexp() {
+        let source = "1 + (2 + (3)) (";
+        let tokens = crate::lex::<Token, _>(source).spanned();
+        let trees = TokenTree::build(tokens, &[(Token::Open, Token::Close)]);
+
+        assert_eq!(
+            to_sexp(&trees, source, false),
+            "(leaf Digit \"1\")\n\
+             (leaf Plus \"+\")\n\
+             (group \"(\" (leaf Digit \"2\") (leaf Plus \"+\") (group \"(\" (leaf Digit \"3\") \")\") \")\")\n\
+             (group \"(\")"
+        );
+        assert_eq!(
+            to_sexp(&trees[2..], source, true),
+            "(group \"(\" 4..13 (leaf Digit \"2\" 5..6) (leaf Plus \"+\" 7..8) \
+             (group \"(\" 9..12 (leaf Digit \"3\" 10..11) \")\") \")\")\n\
+             (group \"(\" 14..15)"
+        );
+    }
+
+    #[test]
+    fn dot() {
+        let source = "(\"a\" 1";
+        let tokens = crate::lex::<Token, _>(source).spanned();
+        let trees = TokenTree::build(tokens, &[(Token::Open, Token::Close)]);
+
+        assert_eq!(
+            to_dot(&trees, source),
+            "digraph tokens {\n  \
+               root [shape=point];\n  \
+               n0 [label=\"Open (\", shape=box];\n  \
+               root -> n0;\n  \
+               n1 [label=\"Unknown \\\"\"];\n  \
+               n0 -> n1;\n  \
+               n2 [label=\"Unknown a\"];\n  \
+               n0 -> n2;\n  \
+               n3 [label=\"Unknown \\\"\"];\n  \
+               n0 -> n3;\n  \
+               n4 [label=\"Digit 1\"];\n  \
+               n0 -> n4;\n\
+             }\n"
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use crate::Span;
+use alloc::{string::String, vec::Vec};
+
+/// `TextEdit` replaces the text at a span
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    /// The span to replace, which is empty for an insertion
+    pub span: Span,
+    /// The replacement, which is empty for a deletion
+    pub text: String,
+}
+
+/// An error produced when applying [`TextEdits`](./struct.TextEdits.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EditError {
+    /// Two edits overlap
+    Overlap { first: Span, second: Span },
+    /// An edit is outside of the source, or not on a `char` boundary
+    OutOfBounds { span: Span },
+}
+
+impl core::fmt::Display for EditError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Overlap { first, second } => {
+                write!(f, "edit at {} overlaps the edit at {}", second, first)
+            }
+            Self::OutOfBounds { span } => write!(f, "edit at {} is out of bounds", span),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EditError {}
+
+/// `TextEdits` accumulates edits to a source, and applies them all at once
+///
+/// Edits use the spans of the original source, and can be added in any order.
+/// Insertions at the same offset are applied in the order they were added.
+/// Edits can touch, but can't overlap
+///
+/// ```rust
+/// # use logos_iterator::{EditError, Span, TextEdits};
+/// let source = "let x = 1;";
+/// let mut edits = TextEdits::new();
+/// edits
+///     .replace(Span::from(4..5), "y")
+///     .insert(0, "// a comment\n")
+///     .delete(Span::from(9..10));
+/// assert_eq!(edits.apply(source).unwrap(), "// a comment\nlet y = 1");
+///
+/// edits.delete(Span::from(3..6));
+/// assert_eq!(
+///     edits.apply(source),
+///     Err(EditError::Overlap { first: Span::from(3..6), second: Span::from(4..5) })
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextEdits {
+    edits: Vec<TextEdit>,
+}
+
+impl TextEdits {
+    /// Create an empty set of edits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the text at `span` with `text`
+    pub fn replace(&mut self, span: Span, text: impl Into<String>) -> &mut Self {
+        self.edits.push(TextEdit {
+            span,
+            text: text.into(),
+        });
+        self
+    }
+
+    /// Insert `text` at the byte `offset`
+    pub fn insert(&mut self, offset: usize, text: impl Into<String>) -> &mut Self {
+        self.replace(Span::from(offset..offset), text)
+    }
+
+    /// Delete the text at `span`
+    pub fn delete(&mut self, span: Span) -> &mut Self {
+        self.replace(span, String::new())
+    }
+
+    /// The number of edits
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    /// Whether there are no edits
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Iterate over the edits, in the order they were added
+    pub fn iter(&self) -> core::slice::Iter<'_, TextEdit> {
+        self.edits.iter()
+    }
+
+    /// Sort the edits by their position, checking that they don't overlap
+    pub fn sorted(&self) -> Result<Vec<&TextEdit>, EditError> {
+        let mut edits = self.edits.iter().collect::<Vec<_>>();
+        edits.sort_by_key(|edit| (edit.span.start, edit.span.end));
+
+        let mut furthest: Option<Span> = None;
+        for edit in &edits {
+            if edit.span.start > edit.span.end {
+                return Err(EditError::OutOfBounds { span: edit.span });
+            }
+            match furthest {
+                Some(first) if first.end > edit.span.start => {
+                    return Err(EditError::Overlap {
+                        first,
+                        second: edit.span,
+                    })
+                }
+                Some(first) if first.end >= edit.span.end => {}
+                _ => furthest = Some(edit.span),
+            }
+        }
+        Ok(edits)
+    }
+
+    /// Apply the edits to `source`, producing the edited source
+    pub fn apply(&self, source: &str) -> Result<String, EditError> {
+        let edits = self.sorted()?;
+        let mut out = String::with_capacity(source.len());
+        let mut pos = 0;
+        for edit in edits {
+            let Span { start, end } = edit.span;
+            if end > source.len()
+                || !source.is_char_boundary(start)
+                || !source.is_char_boundary(end)
+            {
+                return Err(EditError::OutOfBounds { span: edit.span });
+            }
+            out.push_str(&source[pos..start]);
+            out.push_str(&edit.text);
+            pos = end;
+        }
+        out.push_str(&source[pos..]);
+        Ok(out)
+    }
+}
+
+impl Extend<TextEdit> for TextEdits {
+    fn extend<I: IntoIterator<Item = TextEdit>>(&mut self, iter: I) {
+        self.edits.extend(iter)
+    }
+}
+
+impl core::iter::FromIterator<TextEdit> for TextEdits {
+    fn from_iter<I: IntoIterator<Item = TextEdit>>(iter: I) -> Self {
+        Self {
+            edits: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a TextEdits {
+    type Item = &'a TextEdit;
+    type IntoIter = core::slice::Iter<'a, TextEdit>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.edits.iter()
+    }
+}
+
+/// `SpanMapper` maps offsets in an original source to offsets in an edited source, and back
+///
+/// This is created from the [`TextEdits`](./struct.TextEdits.html) that were
+/// applied to the source, so things like cached tokens and diagnostics can be
+/// moved to the edited source. It is also produced by [`rewrite`](./fn.rewrite.html)
+///
+/// ```rust
+/// # use logos_iterator::{Span, SpanMapper, TextEdits};
+/// let source = "let x = 1;";
+/// let mut edits = TextEdits::new();
+/// edits.replace(Span::from(4..5), "value");
+/// let mapper = SpanMapper::new(&edits).unwrap();
+///
+/// let edited = edits.apply(source).unwrap();
+/// let one = mapper.map_span(Span::from(8..9));
+/// assert_eq!(&edited[one], "1");
+///
+/// // part of the replacement doesn't map back to anything in particular
+/// assert_eq!(mapper.try_unmap_span(Span::from(5..6)), None);
+/// assert_eq!(mapper.try_unmap_span(Span::from(4..9)), Some(Span::from(4..5)));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpanMapper {
+    // the (original, edited) spans of each edit, in order
+    edits: Vec<(Span, Span)>,
+}
+
+impl SpanMapper {
+    /// Create a mapper for `edits`, as they would be applied
+    pub fn new(edits: &TextEdits) -> Result<Self, EditError> {
+        let mut mapped = vec![];
+        let mut delta = 0_isize;
+        for edit in edits.sorted()? {
+            let start = (edit.span.start as isize + delta) as usize;
+            mapped.push((edit.span, Span::from(start..start + edit.text.len())));
+            delta += edit.text.len() as isize - (edit.span.end - edit.span.start) as isize;
+        }
+        Ok(Self { edits: mapped })
+    }
+
+    /// Map an `offset` in the original source to the edited source
+    ///
+    /// Offsets inside of an edit are clamped to its replacement
+    pub fn map_offset(&self, offset: usize) -> usize {
+        self.translate(offset, false, false)
+    }
+
+    /// Map a `span` in the original source to the edited source
+    pub fn map_span(&self, span: Span) -> Span {
+        self.translate_span(span, false)
+    }
+
+    /// Map an `offset` in the edited source back to the original source
+    ///
+    /// Offsets inside of an edit are clamped to the text it replaced
+    pub fn unmap_offset(&self, offset: usize) -> usize {
+        self.translate(offset, false, true)
+    }
+
+    /// Map a `span` in the edited source back to the original source
+    pub fn unmap_span(&self, span: Span) -> Span {
+        self.translate_span(span, true)
+    }
+
+    /// Map a `span` in the original source to the edited source, if neither
+    /// end of it is inside of an edit
+    pub fn try_map_span(&self, span: Span) -> Option<Span> {
+        Some(self.map_span(span)).filter(|_| self.is_unambiguous(span, false))
+    }
+
+    /// Map a `span` in the edited source back to the original source, if
+    /// neither end of it is inside of an edit
+    pub fn try_unmap_span(&self, span: Span) -> Option<Span> {
+        Some(self.unmap_span(span)).filter(|_| self.is_unambiguous(span, true))
+    }
+
+    fn is_unambiguous(&self, span: Span, reverse: bool) -> bool {
+        self.edits.iter().all(|&(old, new)| {
+            let from = if reverse { new } else { old };
+            let inside = |offset| from.start < offset && offset < from.end;
+            !inside(span.start) && !inside(span.end)
+        })
+    }
+
+    fn translate_span(&self, span: Span, reverse: bool) -> Span {
+        let start = self.translate(span.start, false, reverse);
+        if span.start == span.end {
+            return Span::from(start..start);
+        }
+        let end = self.translate(span.end, true, reverse);
+        Span::from(start..end.max(start))
+    }
+
+    // `end` offsets belong to the text before them, rather than the text after them
+    fn translate(&self, offset: usize, end: bool, reverse: bool) -> usize {
+        let pick = |&(old, new): &(Span, Span)| if reverse { (new, old) } else { (old, new) };
+        let index = self.edits.partition_point(|edit| {
+            let (from, _) = pick(edit);
+            from.start < offset || (!end && from.start == offset)
+        });
+        let (from, to) = match index.checked_sub(1) {
+            Some(index) => pick(&self.edits[index]),
+            None => return offset,
+        };
+        if end && offset == from.end {
+            return to.end;
+        }
+        if offset < from.end {
+            if !end && offset == from.start {
+                return to.start;
+            }
+            return (to.start + (offset - from.start)).min(to.end);
+        }
+        offset - from.end + to.end
+    }
+
+    /// Iterate over the `(original, edited)` spans of each edit, in order
+    pub fn iter(&self) -> impl Iterator<Item = (Span, Span)> + '_ {
+        self.edits.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply() {
+        let source = "héllo";
+        let mut edits = TextEdits::new();
+        edits
+            .insert(3, "!")
+            .insert(3, "?")
+            .replace(Span::from(0..3), "j");
+        assert_eq!(edits.apply(source).unwrap(), "j!?llo");
+
+        edits.insert(7, "");
+        assert_eq!(
+            edits.apply(source),
+            Err(EditError::OutOfBounds {
+                span: Span::from(7..7)
+            })
+        );
+
+        let mut edits = TextEdits::new();
+        edits.insert(2, "");
+        assert_eq!(
+            edits.apply(source),
+            Err(EditError::OutOfBounds {
+                span: Span::from(2..2)
+            })
+        );
+
+        let mut edits = TextEdits::new();
+        edits
+            .delete(Span::from(0..6))
+            .insert(3, "")
+            .delete(Span::from(4..5));
+        assert_eq!(
+            edits.sorted(),
+            Err(EditError::Overlap {
+                first: Span::from(0..6),
+                second: Span::from(3..3)
+            })
+        );
+    }
+
+    #[test]
+    fn map_longer_replacement() {
+        let source = "ab cd ef";
+        let mut edits = TextEdits::new();
+        edits.replace(Span::from(3..5), "yyyy");
+        let mapper = SpanMapper::new(&edits).unwrap();
+        let edited = edits.apply(source).unwrap();
+
+        // the replaced token maps to all of its replacement
+        assert_eq!(mapper.map_span(Span::from(3..5)), Span::from(3..7));
+        assert_eq!(
+            mapper.try_map_span(Span::from(3..5)),
+            Some(Span::from(3..7))
+        );
+        assert_eq!(&edited[mapper.map_span(Span::from(3..5))], "yyyy");
+        assert_eq!(mapper.map_span(Span::from(0..5)), Span::from(0..7));
+        assert_eq!(mapper.map_span(Span::from(3..8)), Span::from(3..10));
+        assert_eq!(mapper.try_map_span(Span::from(4..5)), None);
+
+        assert_eq!(mapper.unmap_span(Span::from(3..7)), Span::from(3..5));
+        assert_eq!(
+            mapper.try_unmap_span(Span::from(2..7)),
+            Some(Span::from(2..5))
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use crate::Span;
+use alloc::{string::ToString, vec::Vec};
+
+/// `Excerpt` is the lines of a source around a [`Span`](./struct.Span.html)
+///
+/// This is created with [`excerpt`](./fn.excerpt.html). It displays the lines
+/// with their line numbers, and a `^` under the highlighted part of each line
+#[derive(Clone, Debug, PartialEq)]
+pub struct Excerpt<'a> {
+    pub lines: Vec<ExcerptLine<'a>>,
+}
+
+/// `ExcerptLine` is a line of an [`Excerpt`](./struct.Excerpt.html)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ExcerptLine<'a> {
+    /// The 1-based line number
+    pub number: usize,
+    /// The text of the line, without the line ending
+    pub text: &'a str,
+    /// The part of the line that the span covers, relative to the start of the line
+    pub highlight: Option<Span>,
+}
+
+/// Get the lines of `source` that `span` is on, along with `context_lines` lines
+/// before and after it
+///
+/// ```rust
+/// # use logos_iterator::{excerpt, Span};
+/// let source = "let a = 1;\nlet b = ;\nlet c = 3;\n";
+/// let excerpt = excerpt(source, Span::from(19..20), 1);
+/// assert_eq!(excerpt.lines.len(), 3);
+/// assert_eq!(excerpt.lines[1].highlight, Some(Span::from(8..9)));
+/// assert_eq!(
+///     excerpt.to_string(),
+///     "1 | let a = 1;\n2 | let b = ;\n  |         ^\n3 | let c = 3;\n"
+/// );
+/// ```
+pub fn excerpt(source: &str, span: Span, context_lines: usize) -> Excerpt<'_> {
+    let lines = lines_with_spans(source)
+        .map(|(_, span)| (span.start, &source[span]))
+        .collect::<Vec<_>>();
+
+    let line_of = |offset: usize| lines.partition_point(|&(start, _)| start <= offset) - 1;
+    let first = line_of(span.start);
+    let last = line_of(span.end.saturating_sub(1).max(span.start));
+
+    let lines = lines
+        .iter()
+        .enumerate()
+        .skip(first.saturating_sub(context_lines))
+        .take_while(|&(i, _)| i <= last + context_lines)
+        .map(|(i, &(start, text))| {
+            let highlight = if (first..=last).contains(&i) {
+                let lo = span.start.max(start) - start;
+                let hi = span.end.min(start + text.len()).max(start + lo) - start;
+                Some(Span::from(lo..hi))
+            } else {
+                None
+            };
+            ExcerptLine {
+                number: i + 1,
+                text,
+                highlight,
+            }
+        })
+        .collect();
+
+    Excerpt { lines }
+}
+
+/// Iterate over the 1-based line numbers of `source` and the spans of the lines
+///
+/// The spans don't include the `\n` or `\r\n` line endings. A source that
+/// ends with a line ending has an empty last line
+///
+/// ```rust
+/// # use logos_iterator::{lines_with_spans, Span};
+/// let lines = lines_with_spans("a\r\nbc\n").collect::<Vec<_>>();
+/// assert_eq!(lines, vec![(1, Span::from(0..1)), (2, Span::from(3..5)), (3, Span::from(6..6))]);
+/// ```
+pub fn lines_with_spans(source: &str) -> LinesWithSpans<'_> {
+    LinesWithSpans {
+        source,
+        pos: 0,
+        number: 1,
+        done: false,
+    }
+}
+
+/// An iterator over the lines of a source, and their spans
+///
+/// This is created with [`lines_with_spans`](./fn.lines_with_spans.html)
+#[derive(Clone, Debug)]
+pub struct LinesWithSpans<'a> {
+    source: &'a str,
+    pos: usize,
+    number: usize,
+    done: bool,
+}
+
+impl Iterator for LinesWithSpans<'_> {
+    type Item = (usize, Span);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let start = self.pos;
+        let (end, next) = match self.source[start..].find('\n') {
+            Some(i) => {
+                let end = start + i;
+                let text_end = if self.source[..end].ends_with('\r') {
+                    end - 1
+                } else {
+                    end
+                };
+                (text_end, end + 1)
+            }
+            None => {
+                self.done = true;
+                (self.source.len(), self.source.len())
+            }
+        };
+
+        let number = self.number;
+        self.number += 1;
+        self.pos = next;
+        Some((number, Span::from(start..end)))
+    }
+}
+
+impl core::fmt::Display for Excerpt<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let width = self
+            .lines
+            .last()
+            .map(|line| line.number.to_string().len())
+            .unwrap_or(0);
+
+        for line in &self.lines {
+            writeln!(f, "{:>width$} | {}", line.number, line.text, width = width)?;
+            if let Some(highlight) = line.highlight {
+                let count = |range: core::ops::Range<usize>| {
+                    let len = range.len();
+                    line.text.get(range).map_or(len, |s| s.chars().count())
+                };
+                let pad = count(0..highlight.start);
+                let len = count(highlight.start..highlight.end).max(1);
+                writeln!(
+                    f,
+                    "{:>width$} | {:pad$}{}",
+                    "",
+                    "",
+                    "^".repeat(len),
+                    width = width,
+                    pad = pad
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excerpt_lines() {
+        let source = "one\r\ntwo\nthree\nfour\nfive";
+
+        let lines = |span, n| {
+            excerpt(source, span, n)
+                .lines
+                .iter()
+                .map(|line| (line.number, line.text, line.highlight))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            lines(Span::from(10..19), 0),
+            vec![
+                (3, "three", Some(Span::from(1..5))),
+                (4, "four", Some(Span::from(0..4))),
+            ]
+        );
+        assert_eq!(
+            lines(Span::from(6..6), 1),
+            vec![
+                (1, "one", None),
+                (2, "two", Some(Span::from(1..1))),
+                (3, "three", None),
+            ]
+        );
+        assert_eq!(
+            lines(Span::from(24..25), 5).last(),
+            Some(&(5, "five", Some(Span::from(4..4))))
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use crate::{Span, WithSpan};
+use alloc::collections::BTreeMap;
+use alloc::{string::String, vec::Vec};
+
+/// `Expanded` is a token produced by an [`Expander`](./struct.Expander.html)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expanded<T> {
+    /// The token
+    pub item: T,
+    /// The span of the use site, or of the token itself if it wasn't expanded
+    pub span: Span,
+    /// The span of the token in its definition, if it came from an expansion
+    pub def_site: Option<Span>,
+    /// The spans of the identifiers that were expanded to produce this token,
+    /// from the use site in the source to the innermost one
+    ///
+    /// Each identifier after the first is in the definition of the one before it
+    pub call_sites: Vec<Span>,
+}
+
+impl<T> Expanded<T> {
+    /// Where this token came from, for an "in expansion of" trace
+    ///
+    /// This starts with the span of the token in its definition, then has
+    /// each identifier that was expanded to produce it, innermost first, ending
+    /// with the use site in the source. A token that wasn't expanded just has its span
+    ///
+    /// ```rust
+    /// # use logos_iterator::{Expanded, Span};
+    /// let token = Expanded {
+    ///     item: (),
+    ///     span: Span::from(8..9),
+    ///     def_site: Some(Span::from(0..1)),
+    ///     call_sites: vec![Span::from(8..9), Span::from(2..3)],
+    /// };
+    /// let chain = token.origin_chain().collect::<Vec<_>>();
+    /// assert_eq!(chain, vec![Span::from(0..1), Span::from(2..3), Span::from(8..9)]);
+    /// ```
+    pub fn origin_chain(&self) -> impl Iterator<Item = Span> + '_ {
+        let own = match self.def_site {
+            Some(def_site) => def_site,
+            None => self.span,
+        };
+        core::iter::once(own).chain(self.call_sites.iter().rev().copied())
+    }
+}
+
+/// An error produced when expanding tokens
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ExpandError {
+    /// The expansion at this use site went deeper than the limit
+    DepthExceeded { span: Span, depth: usize },
+}
+
+impl core::fmt::Display for ExpandError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::DepthExceeded { span, depth } => {
+                write!(
+                    f,
+                    "expansion at {} exceeded the depth limit of {}",
+                    span, depth
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ExpandError {}
+
+/// `Expander` substitutes identifiers with token sequences, like a macro expansion pass
+///
+/// Identifiers are the tokens where `is_ident` returns `true`, and their names
+/// are their slices of the `source`. Definitions can use other definitions,
+/// which are expanded up to a depth limit
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, Expander, Span};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Ident,
+///     #[regex = "[0-9]+"]
+///     Number,
+///     #[token = "+"]
+///     Plus,
+/// }
+///
+/// let source = "1 + 2 one + two";
+/// let tokens = lex::<Token, _>(source).spanned().collect::<Vec<_>>();
+///
+/// let mut expander = Expander::new(source, |k: &Token| *k == Token::Ident);
+/// expander.define("two", tokens[0..3].to_vec());
+/// let expanded = expander.expand(tokens[4..].to_vec()).unwrap();
+///
+/// let items = expanded.iter().map(|k| k.item).collect::<Vec<_>>();
+/// assert_eq!(items, vec![Token::Plus, Token::Number, Token::Plus, Token::Number]);
+/// assert_eq!(expanded[1].span, Span::from(12..15));
+/// assert_eq!(expanded[1].def_site, Some(Span::from(0..1)));
+/// ```
+pub struct Expander<'a, T, F> {
+    source: &'a str,
+    is_ident: F,
+    defs: BTreeMap<String, Vec<WithSpan<T>>>,
+    max_depth: usize,
+}
+
+impl<'a, T, F> Expander<'a, T, F>
+where
+    T: Clone,
+    F: Fn(&T) -> bool,
+{
+    /// Create an expander for tokens from `source`
+    ///
+    /// The default depth limit is `64`
+    pub fn new(source: &'a str, is_ident: F) -> Self {
+        Self {
+            source,
+            is_ident,
+            defs: BTreeMap::new(),
+            max_depth: 64,
+        }
+    }
+
+    /// Set the depth limit for nested expansions
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Define `name` to expand to the `tokens`, which should be from the same source
+    ///
+    /// This replaces any previous definition
+    pub fn define(
+        &mut self,
+        name: impl Into<String>,
+        tokens: impl IntoIterator<Item = WithSpan<T>>,
+    ) {
+        self.defs.insert(name.into(), tokens.into_iter().collect());
+    }
+
+    /// Remove the definition of `name`
+    pub fn undefine(&mut self, name: &str) {
+        self.defs.remove(name);
+    }
+
+    /// Whether `name` is defined
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.defs.contains_key(name)
+    }
+
+    /// Expand all of the defined identifiers in `tokens`
+    pub fn expand(
+        &self,
+        tokens: impl IntoIterator<Item = WithSpan<T>>,
+    ) -> Result<Vec<Expanded<T>>, ExpandError> {
+        let mut out = vec![];
+        let mut call_sites = vec![];
+        for token in tokens {
+            self.expand_token(token, &mut call_sites, &mut out)?;
+        }
+        Ok(out)
+    }
+
+    fn expand_token(
+        &self,
+        token: WithSpan<T>,
+        call_sites: &mut Vec<Span>,
+        out: &mut Vec<Expanded<T>>,
+    ) -> Result<(), ExpandError> {
+        let use_site = call_sites.first().copied().unwrap_or(token.span);
+        let def = Some(&token.item)
+            .filter(|item| (self.is_ident)(item))
+            .and_then(|_| self.source.get(token.span.start..token.span.end))
+            .and_then(|name| self.defs.get(name));
+
+        let def = match def {
+            Some(def) => def,
+            None => {
+                let span = token.span;
+                out.push(Expanded {
+                    item: token.item,
+                    span: use_site,
+                    def_site: Some(span).filter(|_| !call_sites.is_empty()),
+                    call_sites: call_sites.clone(),
+                });
+                return Ok(());
+            }
+        };
+
+        let depth = call_sites.len();
+        if depth == self.max_depth {
+            return Err(ExpandError::DepthExceeded {
+                span: use_site,
+                depth,
+            });
+        }
+        call_sites.push(token.span);
+        for token in def {
+            self.expand_token(token.clone(), call_sites, out)?;
+        }
+        call_sites.pop();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Ident,
+        #[regex = "[0-9]+"]
+        Number,
+    }
+
+    #[test]
+    fn expand() {
+        let source = "1 2 a b c";
+        let tokens = crate::lex::<Token, _>(source).spanned().collect::<Vec<_>>();
+
+        let mut expander = Expander::new(source, |k: &Token| *k == Token::Ident);
+        expander.define("a", vec![tokens[0], tokens[3]]);
+        expander.define("b", vec![tokens[1]]);
+
+        let expanded = expander.expand(vec![tokens[2], tokens[4]]).unwrap();
+        assert_eq!(
+            expanded[1].origin_chain().collect::<Vec<_>>(),
+            vec![Span::from(2..3), Span::from(6..7), Span::from(4..5)]
+        );
+        assert_eq!(
+            expanded,
+            vec![
+                Expanded {
+                    item: Token::Number,
+                    span: Span::from(4..5),
+                    def_site: Some(Span::from(0..1)),
+                    call_sites: vec![Span::from(4..5)],
+                },
+                Expanded {
+                    item: Token::Number,
+                    span: Span::from(4..5),
+                    def_site: Some(Span::from(2..3)),
+                    call_sites: vec![Span::from(4..5), Span::from(6..7)],
+                },
+                Expanded {
+                    item: Token::Ident,
+                    span: Span::from(8..9),
+                    def_site: None,
+                    call_sites: vec![],
+                },
+            ]
+        );
+
+        expander.define("c", vec![tokens[4]]);
+        assert_eq!(
+            expander.expand(vec![tokens[4]]),
+            Err(ExpandError::DepthExceeded {
+                span: Span::from(8..9),
+                depth: 64
+            })
+        );
+
+        expander.undefine("c");
+        let expander = expander.with_max_depth(1);
+        assert_eq!(
+            expander.expand(vec![tokens[2]]),
+            Err(ExpandError::DepthExceeded {
+                span: Span::from(4..5),
+                depth: 1
+            })
+        );
+    }
+}
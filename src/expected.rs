@@ -0,0 +1,210 @@
+use crate::{TokenName, TokenSet, WithSpan};
+
+/// `ExpectedMessage` formats what was expected and what was found instead
+///
+/// By default this lists all of the expected kinds without an Oxford comma,
+/// e.g. "expected `,`, `)` or identifier, found `;`". When nothing was found,
+/// the message says "end of input"
+///
+/// ```rust
+/// # use logos_iterator::{ExpectedMessage, TokenName, TokenSet};
+/// #[derive(TokenName, PartialEq)]
+/// enum Token {
+///     #[name("`,`")]
+///     Comma,
+///     #[name("`)`")]
+///     Close,
+///     #[name("identifier")]
+///     Ident,
+///     #[name("`;`")]
+///     Semi,
+/// }
+///
+/// let set = TokenSet::new([Token::Comma, Token::Close, Token::Ident]);
+/// let message = ExpectedMessage::new(&set, Some(&Token::Semi));
+/// assert_eq!(message.to_string(), "expected `,`, `)` or identifier, found `;`");
+///
+/// let message = message.oxford_comma(true).limit(2);
+/// assert_eq!(message.to_string(), "expected `,`, `)`, or 1 other, found `;`");
+/// ```
+#[derive(Debug)]
+pub struct ExpectedMessage<'a, T> {
+    expected: &'a TokenSet<T>,
+    found: Option<&'a T>,
+    oxford_comma: bool,
+    limit: Option<usize>,
+}
+
+impl<T> Clone for ExpectedMessage<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ExpectedMessage<'_, T> {}
+
+impl<'a, T> ExpectedMessage<'a, T> {
+    /// Create a message for the `expected` kinds, where `found` is `None` at the end of input
+    pub fn new(expected: &'a TokenSet<T>, found: Option<&'a T>) -> Self {
+        Self {
+            expected,
+            found,
+            oxford_comma: false,
+            limit: None,
+        }
+    }
+
+    /// Whether to put a comma before the final "or", when there are more than two kinds
+    pub fn oxford_comma(mut self, oxford_comma: bool) -> Self {
+        self.oxford_comma = oxford_comma;
+        self
+    }
+
+    /// Only list the first `limit` kinds, counting the rest as "others"
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit.replace(limit.max(1));
+        self
+    }
+}
+
+impl<T> core::fmt::Display for ExpectedMessage<'_, T>
+where
+    T: TokenName,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let found = self.found.map_or("end of input", T::token_name);
+
+        let total = self.expected.len();
+        if total == 0 {
+            return write!(f, "unexpected {}", found);
+        }
+
+        let shown = self.limit.map_or(total, |limit| limit.min(total));
+        let others = total - shown;
+        // the number of items in the list, counting the others as one
+        let items = shown + (others > 0) as usize;
+
+        f.write_str("expected ")?;
+        for (i, kind) in self.expected.iter().take(shown).enumerate() {
+            if i > 0 {
+                f.write_str(separator(i, items, self.oxford_comma))?;
+            }
+            f.write_str(kind.token_name())?;
+        }
+        match others {
+            0 => {}
+            1 => write!(f, "{}1 other", separator(shown, items, self.oxford_comma))?,
+            n => write!(
+                f,
+                "{}{} others",
+                separator(shown, items, self.oxford_comma),
+                n
+            )?,
+        }
+        write!(f, ", found {}", found)
+    }
+}
+
+fn separator(index: usize, items: usize, oxford_comma: bool) -> &'static str {
+    if index + 1 < items {
+        ", "
+    } else if oxford_comma && items > 2 {
+        ", or "
+    } else {
+        " or "
+    }
+}
+
+/// `Expected` is an error for when the next token wasn't one of the expected kinds
+///
+/// This is produced by [`Cursor::expect_any`](./struct.Cursor.html#method.expect_any),
+/// and is displayed with an [`ExpectedMessage`](./struct.ExpectedMessage.html)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expected<T> {
+    /// The kinds that were expected
+    pub expected: TokenSet<T>,
+    /// The token that was found, or `None` at the end of input
+    pub found: Option<WithSpan<T>>,
+}
+
+impl<T> Expected<T> {
+    /// The message for this error, which can be customized
+    pub fn message(&self) -> ExpectedMessage<'_, T> {
+        ExpectedMessage::new(&self.expected, self.found.as_ref().map(|k| &k.item))
+    }
+}
+
+impl<T> core::fmt::Display for Expected<T>
+where
+    T: TokenName,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.message().fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for Expected<T> where T: TokenName + core::fmt::Debug {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(TokenName, PartialEq, Clone, Copy)]
+    enum Token {
+        #[name("`a`")]
+        A,
+        #[name("`b`")]
+        B,
+        #[name("`c`")]
+        C,
+        #[name("`d`")]
+        D,
+    }
+
+    #[test]
+    fn message() {
+        let message = |kinds: &[Token], oxford, limit| {
+            let set = kinds.iter().copied().collect::<TokenSet<_>>();
+            let message = ExpectedMessage::new(&set, Some(&Token::D)).oxford_comma(oxford);
+            match limit {
+                Some(limit) => message.limit(limit).to_string(),
+                None => message.to_string(),
+            }
+        };
+        use Token::*;
+
+        assert_eq!(message(&[], false, None), "unexpected `d`");
+        assert_eq!(message(&[A], true, None), "expected `a`, found `d`");
+        assert_eq!(
+            message(&[A, B], true, None),
+            "expected `a` or `b`, found `d`"
+        );
+        assert_eq!(
+            message(&[A, B, C], false, None),
+            "expected `a`, `b` or `c`, found `d`"
+        );
+        assert_eq!(
+            message(&[A, B, C], true, None),
+            "expected `a`, `b`, or `c`, found `d`"
+        );
+        assert_eq!(
+            message(&[A, B, C], false, Some(1)),
+            "expected `a` or 2 others, found `d`"
+        );
+        assert_eq!(
+            message(&[A, B, C], true, Some(1)),
+            "expected `a` or 2 others, found `d`"
+        );
+        assert_eq!(
+            message(&[A, B, C], false, Some(3)),
+            "expected `a`, `b` or `c`, found `d`"
+        );
+
+        let set = TokenSet::new([A]);
+        assert_eq!(
+            ExpectedMessage::new(&set, None).to_string(),
+            "expected `a`, found end of input"
+        );
+    }
+}
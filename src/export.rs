@@ -0,0 +1,260 @@
+#[cfg(feature = "std")]
+use crate::LineIndex;
+use crate::{TokenStream, WithSpan};
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::io::Write;
+
+/// Write the `tokens` from `source` as CSV, with a header row
+///
+/// The columns are `index, kind, start, end, line, col, slice`, where `kind` is
+/// the `Debug` representation of the token and `line`/`col` are 1-based
+///
+/// ```rust
+/// # use logos_iterator::{write_csv, Span, WithSpan};
+/// let tokens = vec![WithSpan::new('a', Span::from(0..1)), WithSpan::new(',', Span::from(2..3))];
+/// let mut out = vec![];
+/// write_csv(&mut out, "a\n,", &tokens).unwrap();
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     "index,kind,start,end,line,col,slice\n0,'a',0,1,1,1,a\n1,\"','\",2,3,2,1,\",\"\n"
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn write_csv<T>(out: impl Write, source: &str, tokens: &[WithSpan<T>]) -> std::io::Result<()>
+where
+    T: core::fmt::Debug,
+{
+    write_table(out, source, tokens, ',', |s| {
+        if s.contains(&[',', '"', '\n', '\r'][..]) {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        } else {
+            s.to_string()
+        }
+    })
+}
+
+/// Write the `tokens` from `source` as TSV, with a header row
+///
+/// This has the same columns as [`write_csv`](./fn.write_csv.html). Tabs,
+/// newlines, carriage returns and backslashes in the fields are escaped as `\t`, `\n`, `\r` and `\\`
+#[cfg(feature = "std")]
+pub fn write_tsv<T>(out: impl Write, source: &str, tokens: &[WithSpan<T>]) -> std::io::Result<()>
+where
+    T: core::fmt::Debug,
+{
+    write_table(out, source, tokens, '\t', |s| {
+        s.replace('\\', "\\\\")
+            .replace('\t', "\\t")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+    })
+}
+
+#[cfg(feature = "std")]
+fn write_table<T>(
+    mut out: impl Write,
+    source: &str,
+    tokens: &[WithSpan<T>],
+    sep: char,
+    escape: impl Fn(&str) -> String,
+) -> std::io::Result<()>
+where
+    T: core::fmt::Debug,
+{
+    let index = LineIndex::new(source);
+    let header = ["index", "kind", "start", "end", "line", "col", "slice"];
+    writeln!(out, "{}", header.join(&sep.to_string()))?;
+
+    for (i, token) in tokens.iter().enumerate() {
+        let pos = index.line_col(token.span.start);
+        let slice = source.get(token.span.start..token.span.end).unwrap_or("");
+        writeln!(
+            out,
+            "{i}{sep}{kind}{sep}{start}{sep}{end}{sep}{line}{sep}{col}{sep}{slice}",
+            i = i,
+            kind = escape(&format!("{:?}", token.item)),
+            start = token.span.start,
+            end = token.span.end,
+            line = pos.line,
+            col = pos.column,
+            slice = escape(slice),
+            sep = sep,
+        )?;
+    }
+    Ok(())
+}
+
+/// Convert the `tokens` from `source` into a JSON array
+///
+/// Each token is an object of `{"kind", "span": [start, end], "text"}`, where
+/// `kind` is the `Debug` representation of the token
+///
+/// ```rust
+/// # use logos_iterator::{to_json, Span, TokenStream, WithSpan};
+/// let tokens = TokenStream::new(vec![WithSpan::new('"', Span::from(0..1))]);
+/// assert_eq!(
+///     to_json(&tokens, "\""),
+///     r#"[{"kind":"'\"'","span":[0,1],"text":"\""}]"#
+/// );
+/// ```
+pub fn to_json<T>(tokens: &TokenStream<T>, source: &str) -> String
+where
+    T: core::fmt::Debug,
+{
+    let mut out = String::from("[");
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        json_token(&mut out, source, token);
+    }
+    out.push(']');
+    out
+}
+
+/// Write each of the `tokens` from `source` to `out` as a line of JSON
+///
+/// This has the same objects as [`to_json`](./fn.to_json.html), but the tokens
+/// are written as they are produced, so a lexer can be written without
+/// collecting it. This returns how many tokens were written
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, write_json_lines};
+/// # #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// # enum Token {
+/// #     #[end]
+/// #     Eof,
+/// #     #[error]
+/// #     Unknown,
+/// #     #[regex = "[a-z]+"]
+/// #     Word,
+/// # }
+/// let source = "foo bar";
+/// let mut out = vec![];
+/// let count = write_json_lines(&mut out, source, lex::<Token, _>(source).spanned()).unwrap();
+/// assert_eq!(count, 2);
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     "{\"kind\":\"Word\",\"span\":[0,3],\"text\":\"foo\"}\n\
+///      {\"kind\":\"Word\",\"span\":[4,7],\"text\":\"bar\"}\n"
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn write_json_lines<T>(
+    mut out: impl Write,
+    source: &str,
+    tokens: impl IntoIterator<Item = WithSpan<T>>,
+) -> std::io::Result<usize>
+where
+    T: core::fmt::Debug,
+{
+    let mut line = String::new();
+    let mut count = 0;
+    for token in tokens {
+        line.clear();
+        json_token(&mut line, source, &token);
+        line.push('\n');
+        out.write_all(line.as_bytes())?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn json_token<T>(out: &mut String, source: &str, token: &WithSpan<T>)
+where
+    T: core::fmt::Debug,
+{
+    use core::fmt::Write as _;
+    let text = source.get(token.span.start..token.span.end).unwrap_or("");
+    let _ = write!(
+        out,
+        r#"{{"kind":{},"span":[{},{}],"text":{}}}"#,
+        json_string(&format!("{:?}", token.item)),
+        token.span.start,
+        token.span.end,
+        json_string(text)
+    );
+}
+
+pub(crate) fn json_string(s: &str) -> String {
+    use core::fmt::Write as _;
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+        #[regex = "\"[^\"]*\""]
+        Str,
+        #[token = "\n"]
+        NewLine,
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn tables() {
+        let source = "abc\n\"x\ty\"";
+        let tokens = crate::lex::<Token, _>(source).spanned().collect::<Vec<_>>();
+
+        let mut csv = vec![];
+        write_csv(&mut csv, source, &tokens).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "index,kind,start,end,line,col,slice\n\
+             0,Word,0,3,1,1,abc\n\
+             1,NewLine,3,4,1,4,\"\n\"\n\
+             2,Str,4,9,2,1,\"\"\"x\ty\"\"\"\n"
+        );
+
+        let mut tsv = vec![];
+        write_tsv(&mut tsv, source, &tokens).unwrap();
+        assert_eq!(
+            String::from_utf8(tsv).unwrap(),
+            "index\tkind\tstart\tend\tline\tcol\tslice\n\
+             0\tWord\t0\t3\t1\t1\tabc\n\
+             1\tNewLine\t3\t4\t1\t4\t\\n\n\
+             2\tStr\t4\t9\t2\t1\t\"x\\ty\"\n"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let source = "abc\n\"x\ty\u{1}\"";
+        let tokens = crate::lex::<Token, _>(source)
+            .spanned()
+            .collect::<TokenStream<_>>();
+        assert_eq!(
+            to_json(&tokens, source),
+            r#"[{"kind":"Word","span":[0,3],"text":"abc"},{"kind":"NewLine","span":[3,4],"text":"\n"},{"kind":"Str","span":[4,10],"text":"\"x\ty\u0001\""}]"#
+        );
+        assert_eq!(to_json(&TokenStream::<Token>::default(), ""), "[]");
+    }
+}
@@ -0,0 +1,186 @@
+//! A C FFI layer for token dumps
+//!
+//! Use [`export_c_lexer!`](../macro.export_c_lexer.html) to generate the
+//! `extern "C"` functions for a token type
+use alloc::{boxed::Box, vec::Vec};
+
+/// `FfiToken` is a spanned token for C callers
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FfiToken {
+    /// The discriminant of the token
+    pub kind: u32,
+    /// The byte offset of the start of the token
+    pub start: usize,
+    /// The byte offset of the end of the token
+    pub end: usize,
+}
+
+/// `FfiTokens` is an array of [`FfiToken`](./struct.FfiToken.html)s owned by Rust
+///
+/// `ptr` is null if the input couldn't be lexed. This must be freed with
+/// [`free_tokens`](./fn.free_tokens.html)
+#[repr(C)]
+#[derive(Debug)]
+pub struct FfiTokens {
+    pub ptr: *mut FfiToken,
+    pub len: usize,
+}
+
+impl FfiTokens {
+    const NULL: Self = Self {
+        ptr: core::ptr::null_mut(),
+        len: 0,
+    };
+}
+
+/// Lex the UTF-8 buffer at `ptr` with the length `len`, using `kind` to get the
+/// discriminant of each token
+///
+/// If `ptr` is null or the buffer isn't UTF-8, the returned `ptr` is null
+///
+/// # Safety
+/// `ptr` must be null, or valid for reads of `len` bytes
+pub unsafe fn lex_buffer<T>(ptr: *const u8, len: usize, kind: impl Fn(T) -> u32) -> FfiTokens
+where
+    T: Clone + PartialEq + ::logos::Logos + for<'a> ::logos::source::WithSource<&'a str>,
+{
+    if ptr.is_null() {
+        return FfiTokens::NULL;
+    }
+    let bytes = core::slice::from_raw_parts(ptr, len);
+    let source = match core::str::from_utf8(bytes) {
+        Ok(source) => source,
+        Err(..) => return FfiTokens::NULL,
+    };
+
+    let tokens = crate::lex::<T, _>(source)
+        .spanned()
+        .map(|k| FfiToken {
+            kind: kind(k.item),
+            start: k.span.start,
+            end: k.span.end,
+        })
+        .collect::<Vec<_>>()
+        .into_boxed_slice();
+
+    let len = tokens.len();
+    FfiTokens {
+        ptr: Box::into_raw(tokens) as *mut FfiToken,
+        len,
+    }
+}
+
+/// Free the `tokens` returned by [`lex_buffer`](./fn.lex_buffer.html)
+///
+/// # Safety
+/// `tokens` must have come from `lex_buffer`, and must not be used afterwards
+pub unsafe fn free_tokens(tokens: FfiTokens) {
+    if tokens.ptr.is_null() {
+        return;
+    }
+    let slice = core::ptr::slice_from_raw_parts_mut(tokens.ptr, tokens.len);
+    drop(Box::from_raw(slice));
+}
+
+/// Generate `extern "C"` functions for lexing a buffer into an array of
+/// [`FfiToken`](./ffi/struct.FfiToken.html)s, and for freeing that array
+///
+/// The token kind is the discriminant of the token, so the token type should
+/// be a fieldless enum. This needs the `ffi` feature
+///
+/// ```rust
+/// # use logos::Logos;
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Word,
+/// }
+///
+/// // generates:
+/// // FfiTokens my_lex(const uint8_t *ptr, size_t len);
+/// // void my_free(FfiTokens tokens);
+/// logos_iterator::export_c_lexer!(my_lex, my_free, Token);
+///
+/// let source = "hello world";
+/// let tokens = unsafe { my_lex(source.as_ptr(), source.len()) };
+/// assert_eq!(tokens.len, 2);
+/// unsafe { my_free(tokens) };
+/// ```
+#[macro_export]
+macro_rules! export_c_lexer {
+    ($lex:ident, $free:ident, $token:ty) => {
+        /// Lex the UTF-8 buffer at `ptr` with the length `len`
+        ///
+        /// # Safety
+        /// `ptr` must be null, or valid for reads of `len` bytes
+        #[no_mangle]
+        pub unsafe extern "C" fn $lex(ptr: *const u8, len: usize) -> $crate::ffi::FfiTokens {
+            $crate::ffi::lex_buffer::<$token>(ptr, len, |k| k as u32)
+        }
+
+        /// Free the tokens returned by the lex function
+        ///
+        /// # Safety
+        /// `tokens` must have come from the lex function, and must not be used afterwards
+        #[no_mangle]
+        pub unsafe extern "C" fn $free(tokens: $crate::ffi::FfiTokens) {
+            $crate::ffi::free_tokens(tokens)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+        #[regex = "[0-9]+"]
+        Number,
+    }
+
+    crate::export_c_lexer!(test_lex, test_free, Token);
+
+    #[test]
+    fn export_c_lexer() {
+        let source = "abc 12";
+        unsafe {
+            let tokens = test_lex(source.as_ptr(), source.len());
+            assert_eq!(
+                core::slice::from_raw_parts(tokens.ptr, tokens.len),
+                &[
+                    FfiToken {
+                        kind: Token::Word as u32,
+                        start: 0,
+                        end: 3
+                    },
+                    FfiToken {
+                        kind: Token::Number as u32,
+                        start: 4,
+                        end: 6
+                    },
+                ]
+            );
+            test_free(tokens);
+
+            let invalid = [b'a', 0xff];
+            let tokens = test_lex(invalid.as_ptr(), invalid.len());
+            assert!(tokens.ptr.is_null());
+            test_free(tokens);
+
+            assert!(test_lex(core::ptr::null(), 0).ptr.is_null());
+        }
+    }
+}
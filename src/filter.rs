@@ -0,0 +1,465 @@
+use crate::{TokenStream, WithSpan};
+use alloc::collections::VecDeque;
+use alloc::{boxed::Box, vec::Vec};
+
+/// `TokenFilter` is a post-processing pass over a stream of spanned tokens
+///
+/// Tokens are pushed into the filter one at a time, and the filter pushes the
+/// tokens it produces to `out`. A filter can hold on to tokens (e.g. for
+/// lookahead) and produce them later, or when the input is
+/// [`finish`](#method.finish)ed
+///
+/// This is implemented for closures of `FnMut(WithSpan<T>, &mut Vec<WithSpan<T>>)`
+pub trait TokenFilter<T> {
+    /// Push a `token` into the filter, pushing any produced tokens to `out`
+    fn push(&mut self, token: WithSpan<T>, out: &mut Vec<WithSpan<T>>);
+
+    /// The input has ended, push any remaining tokens to `out`
+    fn finish(&mut self, out: &mut Vec<WithSpan<T>>) {
+        let _ = out;
+    }
+
+    /// The name of this filter
+    fn name(&self) -> &str {
+        core::any::type_name::<Self>()
+    }
+}
+
+impl<T, F> TokenFilter<T> for F
+where
+    F: FnMut(WithSpan<T>, &mut Vec<WithSpan<T>>),
+{
+    fn push(&mut self, token: WithSpan<T>, out: &mut Vec<WithSpan<T>>) {
+        self(token, out)
+    }
+}
+
+/// A [`TokenFilter`](./trait.TokenFilter.html) that maps each token
+///
+/// This is useful for things like resolving keywords from identifiers
+pub struct MapFilter<F>(pub F);
+
+impl<T, F> TokenFilter<T> for MapFilter<F>
+where
+    F: FnMut(WithSpan<T>) -> WithSpan<T>,
+{
+    fn push(&mut self, token: WithSpan<T>, out: &mut Vec<WithSpan<T>>) {
+        out.push((self.0)(token))
+    }
+}
+
+/// A [`TokenFilter`](./trait.TokenFilter.html) that only keeps the tokens where
+/// the predicate returns `true`
+///
+/// This is useful for things like removing trivia
+pub struct RetainFilter<F>(pub F);
+
+impl<T, F> TokenFilter<T> for RetainFilter<F>
+where
+    F: FnMut(&WithSpan<T>) -> bool,
+{
+    fn push(&mut self, token: WithSpan<T>, out: &mut Vec<WithSpan<T>>) {
+        if (self.0)(&token) {
+            out.push(token)
+        }
+    }
+}
+
+/// A [`TokenFilter`](./trait.TokenFilter.html) that merges runs of the same token,
+/// like [`TokenIteratorExt::coalesce`](./trait.TokenIteratorExt.html#method.coalesce)
+pub struct CoalesceFilter<T, F> {
+    kind: F,
+    pending: Option<WithSpan<T>>,
+}
+
+impl<T, F> CoalesceFilter<T, F>
+where
+    F: FnMut(&T) -> bool,
+{
+    /// Merge runs of tokens where `kind` returns `true`
+    pub fn new(kind: F) -> Self {
+        Self {
+            kind,
+            pending: None,
+        }
+    }
+}
+
+impl<T, F> TokenFilter<T> for CoalesceFilter<T, F>
+where
+    T: PartialEq,
+    F: FnMut(&T) -> bool,
+{
+    fn push(&mut self, token: WithSpan<T>, out: &mut Vec<WithSpan<T>>) {
+        match &mut self.pending {
+            Some(pending) if pending.item == token.item => {
+                pending.span = pending.span.join(token.span);
+                return;
+            }
+            _ => out.extend(self.pending.take()),
+        }
+
+        if (self.kind)(&token.item) {
+            self.pending.replace(token);
+        } else {
+            out.push(token)
+        }
+    }
+
+    fn finish(&mut self, out: &mut Vec<WithSpan<T>>) {
+        out.extend(self.pending.take())
+    }
+}
+
+/// `Pipeline` composes [`TokenFilter`](./trait.TokenFilter.html)s, running them in order
+///
+/// A `Pipeline` is also a `TokenFilter`, so pipelines can be nested
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, MapFilter, Pipeline, RetainFilter, WithSpan};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Ident,
+///     #[token = "#"]
+///     Comment,
+///     Keyword,
+/// }
+///
+/// let input = "let # x";
+/// let tokens = Pipeline::new()
+///     .then(RetainFilter(|k: &WithSpan<Token>| k.item != Token::Comment))
+///     .then(MapFilter(|mut k: WithSpan<Token>| {
+///         if &input[k.span] == "let" {
+///             k.item = Token::Keyword
+///         }
+///         k
+///     }))
+///     .run(lex::<Token, _>(input).spanned())
+///     .map(|k| k.item)
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(tokens, vec![Token::Keyword, Token::Ident]);
+/// ```
+pub struct Pipeline<'a, T> {
+    filters: Vec<Box<dyn TokenFilter<T> + 'a>>,
+    stats: Vec<PassStats>,
+    // the input and output of the current pass, reused between passes
+    input: Vec<WithSpan<T>>,
+    output: Vec<WithSpan<T>>,
+    #[cfg(feature = "std")]
+    timing: bool,
+}
+
+impl<T> Default for Pipeline<'_, T> {
+    fn default() -> Self {
+        Self {
+            filters: vec![],
+            stats: vec![],
+            input: vec![],
+            output: vec![],
+            #[cfg(feature = "std")]
+            timing: false,
+        }
+    }
+}
+
+/// `PassStats` records what a filter of a [`Pipeline`](./struct.Pipeline.html) has done
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PassStats {
+    /// The number of tokens pushed into the filter
+    pub tokens_in: usize,
+    /// The number of tokens the filter produced
+    pub tokens_out: usize,
+    /// The time spent in the filter, if the pipeline is
+    /// [timed](./struct.Pipeline.html#method.with_timing)
+    pub elapsed: Option<core::time::Duration>,
+}
+
+impl<'a, T> Pipeline<'a, T> {
+    /// Create an empty pipeline, which yields the tokens unchanged
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `filter` to the end of the pipeline
+    pub fn then(mut self, filter: impl TokenFilter<T> + 'a) -> Self {
+        self.filters.push(Box::new(filter));
+        self.stats.push(PassStats::default());
+        self
+    }
+
+    /// Record the time spent in each filter, if `timing` is `true`
+    ///
+    /// This is off by default. [`apply`](#method.apply) times each filter once,
+    /// but [`run`](#method.run) times each filter for every token, which can
+    /// cost more than the filters themselves. This can't be used on targets
+    /// without a clock, like `wasm32-unknown-unknown`
+    #[cfg(feature = "std")]
+    pub fn with_timing(mut self, timing: bool) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    /// The names of the filters, in the order they are run
+    pub fn passes(&self) -> impl Iterator<Item = &str> + '_ {
+        self.filters.iter().map(|filter| filter.name())
+    }
+
+    /// The names of the filters and what they have done, in the order they are run
+    pub fn pass_stats(&self) -> impl Iterator<Item = (&str, PassStats)> + '_ {
+        self.passes().zip(self.stats.iter().copied())
+    }
+
+    /// Run the pipeline over all of the `tokens`
+    ///
+    /// Each filter is run over all of the tokens before the next filter
+    pub fn apply(&mut self, tokens: impl IntoIterator<Item = WithSpan<T>>) -> TokenStream<T> {
+        self.input.extend(tokens);
+        let mut out = vec![];
+        self.feed(true, &mut out);
+        TokenStream::new(out)
+    }
+
+    /// Lazily run the pipeline over the `tokens`, as an iterator
+    pub fn run<I>(self, tokens: I) -> PipelineIter<'a, I::IntoIter, T>
+    where
+        I: IntoIterator<Item = WithSpan<T>>,
+    {
+        PipelineIter {
+            pipeline: self,
+            iter: tokens.into_iter(),
+            buf: VecDeque::new(),
+            out: vec![],
+            done: false,
+        }
+    }
+
+    // run the tokens in `input` through each filter, pushing what the last one produces to `out`
+    fn feed(&mut self, finish: bool, out: &mut Vec<WithSpan<T>>) {
+        for (filter, stats) in self.filters.iter_mut().zip(&mut self.stats) {
+            #[cfg(feature = "std")]
+            let start = if self.timing {
+                Some(std::time::Instant::now())
+            } else {
+                None
+            };
+
+            stats.tokens_in += self.input.len();
+            for token in self.input.drain(..) {
+                filter.push(token, &mut self.output)
+            }
+            if finish {
+                filter.finish(&mut self.output)
+            }
+            stats.tokens_out += self.output.len();
+            core::mem::swap(&mut self.input, &mut self.output);
+
+            #[cfg(feature = "std")]
+            if let Some(start) = start {
+                *stats.elapsed.get_or_insert_with(Default::default) += start.elapsed();
+            }
+        }
+        out.append(&mut self.input);
+    }
+}
+
+impl<T> TokenFilter<T> for Pipeline<'_, T> {
+    fn push(&mut self, token: WithSpan<T>, out: &mut Vec<WithSpan<T>>) {
+        self.input.push(token);
+        self.feed(false, out)
+    }
+
+    fn finish(&mut self, out: &mut Vec<WithSpan<T>>) {
+        self.feed(true, out)
+    }
+}
+
+/// An iterator that lazily runs a [`Pipeline`](./struct.Pipeline.html)
+///
+/// This is created with [`Pipeline::run`](./struct.Pipeline.html#method.run)
+pub struct PipelineIter<'a, I, T> {
+    pipeline: Pipeline<'a, T>,
+    iter: I,
+    buf: VecDeque<WithSpan<T>>,
+    out: Vec<WithSpan<T>>,
+    done: bool,
+}
+
+impl<'a, I, T> PipelineIter<'a, I, T> {
+    /// The pipeline that is being run
+    pub fn pipeline(&self) -> &Pipeline<'a, T> {
+        &self.pipeline
+    }
+}
+
+impl<I, T> Iterator for PipelineIter<'_, I, T>
+where
+    I: Iterator<Item = WithSpan<T>>,
+{
+    type Item = WithSpan<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buf.is_empty() && !self.done {
+            match self.iter.next() {
+                Some(token) => self.pipeline.push(token, &mut self.out),
+                None => {
+                    self.pipeline.finish(&mut self.out);
+                    self.done = true;
+                }
+            }
+            self.buf.extend(self.out.drain(..));
+        }
+        self.buf.pop_front()
+    }
+}
+
+/// `LexPipeline` is a spanned lexer followed by a [`Pipeline`](./struct.Pipeline.html)
+/// of [`TokenFilter`](./trait.TokenFilter.html)s, as one iterator
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{LexPipeline, RetainFilter, WithSpan};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Ident,
+///     #[regex = "#[^\n]*"]
+///     Comment,
+/// }
+///
+/// let mut lexer = LexPipeline::<Token, _>::new("a # b\nc")
+///     .then(RetainFilter(|k: &WithSpan<Token>| k.item != Token::Comment));
+/// assert_eq!(lexer.by_ref().count(), 3);
+///
+/// let (_, stats) = lexer.pass_stats().next().unwrap();
+/// assert_eq!((stats.tokens_in, stats.tokens_out), (4, 3));
+/// ```
+pub struct LexPipeline<'a, T, S>
+where
+    T: PartialEq + ::logos::Logos,
+{
+    iter: PipelineIter<'a, crate::SpannedLexer<T, S>, T>,
+}
+
+impl<'a, 's, T, S> LexPipeline<'a, T, S>
+where
+    T: PartialEq + ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'s>,
+{
+    /// Create a pipeline lexing the source `S`, without any filters
+    pub fn new(s: S) -> Self {
+        Self {
+            iter: PipelineIter {
+                pipeline: Pipeline::new(),
+                iter: crate::SpannedLexer::new(s),
+                buf: VecDeque::new(),
+                out: vec![],
+                done: false,
+            },
+        }
+    }
+}
+
+impl<'a, T, S> LexPipeline<'a, T, S>
+where
+    T: PartialEq + ::logos::Logos,
+{
+    /// Add `filter` to the end of the pipeline
+    pub fn then(mut self, filter: impl TokenFilter<T> + 'a) -> Self {
+        let pipeline = core::mem::take(&mut self.iter.pipeline);
+        self.iter.pipeline = pipeline.then(filter);
+        self
+    }
+
+    /// The names of the filters, in the order they are run
+    pub fn passes(&self) -> impl Iterator<Item = &str> + '_ {
+        self.iter.pipeline.passes()
+    }
+
+    /// The names of the filters and what they have done, in the order they are run
+    pub fn pass_stats(&self) -> impl Iterator<Item = (&str, PassStats)> + '_ {
+        self.iter.pipeline.pass_stats()
+    }
+}
+
+impl<T, S> Iterator for LexPipeline<'_, T, S>
+where
+    T: PartialEq + ::logos::Logos,
+    crate::SpannedLexer<T, S>: Iterator<Item = WithSpan<T>>,
+{
+    type Item = WithSpan<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Span;
+
+    #[test]
+    fn pipeline() {
+        let tokens = crate::testing::chars("aabbbcdd");
+
+        let mut pipeline = Pipeline::new()
+            .then(CoalesceFilter::new(|&c: &char| c != 'c'))
+            .then(RetainFilter(|k: &WithSpan<char>| k.item != 'b'))
+            .then(|k: WithSpan<char>, out: &mut Vec<WithSpan<char>>| {
+                out.push(k);
+                out.push(WithSpan::new('!', Span::from(k.span.end..k.span.end)));
+            });
+        assert_eq!(pipeline.passes().count(), 3);
+        assert_eq!(
+            pipeline.pass_stats().next().unwrap().1,
+            PassStats::default()
+        );
+
+        let expected = vec![
+            WithSpan::new('a', Span::from(0..2)),
+            WithSpan::new('!', Span::from(2..2)),
+            WithSpan::new('c', Span::from(5..6)),
+            WithSpan::new('!', Span::from(6..6)),
+            WithSpan::new('d', Span::from(6..8)),
+            WithSpan::new('!', Span::from(8..8)),
+        ];
+        assert_eq!(pipeline.apply(tokens.clone()).into_inner(), expected);
+
+        let counts = pipeline
+            .pass_stats()
+            .map(|(_, stats)| (stats.tokens_in, stats.tokens_out))
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![(8, 4), (4, 3), (3, 6)]);
+        assert!(pipeline
+            .pass_stats()
+            .all(|(_, stats)| stats.elapsed.is_none()));
+        assert_eq!(pipeline.run(tokens).collect::<Vec<_>>(), expected);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn timing() {
+        let tokens = (0..4).map(|i| WithSpan::new(i, Span::from(i..i + 1)));
+        let mut pipeline = Pipeline::new()
+            .then(RetainFilter(|k: &WithSpan<usize>| k.item < 2))
+            .with_timing(true);
+        assert_eq!(pipeline.apply(tokens.clone()).len(), 2);
+        assert!(pipeline
+            .pass_stats()
+            .all(|(_, stats)| stats.elapsed.is_some()));
+
+        let mut lazy = pipeline.run(tokens);
+        assert_eq!(lazy.by_ref().count(), 2);
+        let (_, stats) = lazy.pipeline().pass_stats().next().unwrap();
+        assert_eq!((stats.tokens_in, stats.tokens_out), (8, 4));
+    }
+}
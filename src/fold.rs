@@ -0,0 +1,287 @@
+use crate::{Assoc, Operator, PrecedenceTable, Span, WithSpan};
+use alloc::vec::Vec;
+
+/// `FoldError` is a problem found by [`fold_expr`](./fn.fold_expr.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FoldError {
+    /// A token that can't be at this position
+    Unexpected { span: Span },
+    /// An operand was expected at this (empty) span
+    MissingOperand { span: Span },
+    /// A non-associative operator was chained with one of the same precedence
+    NonAssociative { span: Span },
+    /// An opening bracket that was never closed
+    Unclosed { open: Span },
+}
+
+impl core::fmt::Display for FoldError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Unexpected { span } => write!(f, "unexpected token at {}", span),
+            Self::MissingOperand { span } => write!(f, "expected an operand at {}", span),
+            Self::NonAssociative { span } => {
+                write!(f, "operator at {} can't be chained", span)
+            }
+            Self::Unclosed { open } => write!(f, "unclosed delimiter at {}", open),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FoldError {}
+
+enum Pending<T> {
+    Prefix(WithSpan<T>, Operator),
+    Infix(WithSpan<T>, Operator),
+    Postfix(WithSpan<T>),
+    Open(WithSpan<T>),
+}
+
+/// Fold the `tokens` of an infix expression into a single spanned value
+///
+/// Operators are looked up in the `table`, and the bracket `pairs` of `(open, close)`
+/// kinds group subexpressions. Every other token is given to `mk_operand`,
+/// which returns `None` if it isn't an operand. Unary (prefix and postfix) operators
+/// are applied with `mk_unary`, and infix operators with `mk_binary`.
+///
+/// The span of each value covers its operator and operands, including any brackets
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, fold_expr, Assoc, PrecedenceTable};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[0-9]+"]
+///     Number,
+///     #[token = "+"]
+///     Plus,
+///     #[token = "-"]
+///     Minus,
+///     #[token = "*"]
+///     Star,
+///     #[token = "("]
+///     Open,
+///     #[token = ")"]
+///     Close,
+/// }
+///
+/// let source = "2 * (3 - -1) + 4";
+/// let table = PrecedenceTable::new()
+///     .infix(Token::Plus, 1, Assoc::Left)
+///     .infix(Token::Minus, 1, Assoc::Left)
+///     .infix(Token::Star, 2, Assoc::Left)
+///     .prefix(Token::Minus, 3);
+///
+/// let value = fold_expr(
+///     lex::<Token, _>(source).spanned(),
+///     &table,
+///     &[(Token::Open, Token::Close)],
+///     |k| match k.item {
+///         Token::Number => source[k.span].parse::<i32>().ok(),
+///         _ => None,
+///     },
+///     |_op, a| -a.item,
+///     |a, op, b| match op.item {
+///         Token::Plus => a.item + b.item,
+///         Token::Minus => a.item - b.item,
+///         _ => a.item * b.item,
+///     },
+/// )
+/// .unwrap();
+///
+/// assert_eq!(value.item, 12);
+/// assert_eq!(&source[value.span], source);
+/// ```
+pub fn fold_expr<T, E, I>(
+    tokens: I,
+    table: &PrecedenceTable<T>,
+    pairs: &[(T, T)],
+    mut mk_operand: impl FnMut(&WithSpan<T>) -> Option<E>,
+    mut mk_unary: impl FnMut(WithSpan<T>, WithSpan<E>) -> E,
+    mut mk_binary: impl FnMut(WithSpan<E>, WithSpan<T>, WithSpan<E>) -> E,
+) -> Result<WithSpan<E>, FoldError>
+where
+    T: PartialEq,
+    I: IntoIterator<Item = WithSpan<T>>,
+{
+    let mut operands: Vec<WithSpan<E>> = vec![];
+    let mut pending: Vec<Pending<T>> = vec![];
+    let mut expect_operand = true;
+    let mut end = Span::from(0..0);
+
+    // apply the top pending operator to the top operand(s)
+    let mut reduce = |operands: &mut Vec<WithSpan<E>>, pending: Pending<T>| match pending {
+        Pending::Prefix(op, _) => {
+            let operand = operands.pop().expect("prefix operand");
+            let span = op.span.join(operand.span);
+            operands.push(WithSpan::new(mk_unary(op, operand), span));
+        }
+        Pending::Postfix(op) => {
+            let operand = operands.pop().expect("postfix operand");
+            let span = operand.span.join(op.span);
+            operands.push(WithSpan::new(mk_unary(op, operand), span));
+        }
+        Pending::Infix(op, _) => {
+            let rhs = operands.pop().expect("right operand");
+            let lhs = operands.pop().expect("left operand");
+            let span = lhs.span.join(rhs.span);
+            operands.push(WithSpan::new(mk_binary(lhs, op, rhs), span));
+        }
+        Pending::Open(..) => unreachable!("brackets aren't reduced"),
+    };
+
+    for token in tokens {
+        end = Span::from(token.span.end..token.span.end);
+
+        if expect_operand {
+            if pairs.iter().any(|(open, _)| *open == token.item) {
+                pending.push(Pending::Open(token));
+            } else if let Some(op) = table.prefix_of(&token.item) {
+                pending.push(Pending::Prefix(token, op));
+            } else {
+                let span = token.span;
+                let operand = mk_operand(&token).ok_or(FoldError::Unexpected { span })?;
+                operands.push(WithSpan::new(operand, span));
+                expect_operand = false;
+            }
+            continue;
+        }
+
+        if let Some(op) = table.postfix_of(&token.item) {
+            unwind(&mut operands, &mut pending, op, token.span, &mut reduce)?;
+            reduce(&mut operands, Pending::Postfix(token));
+        } else if let Some(op) = table.infix_of(&token.item) {
+            unwind(&mut operands, &mut pending, op, token.span, &mut reduce)?;
+            pending.push(Pending::Infix(token, op));
+            expect_operand = true;
+        } else if pairs.iter().any(|(_, close)| *close == token.item) {
+            loop {
+                match pending.pop() {
+                    Some(Pending::Open(open)) => {
+                        if !pairs
+                            .iter()
+                            .any(|(o, c)| *o == open.item && *c == token.item)
+                        {
+                            return Err(FoldError::Unexpected { span: token.span });
+                        }
+                        let operand = operands.last_mut().expect("grouped operand");
+                        operand.span = open.span.join(token.span);
+                        break;
+                    }
+                    Some(top) => reduce(&mut operands, top),
+                    None => return Err(FoldError::Unexpected { span: token.span }),
+                }
+            }
+        } else {
+            return Err(FoldError::Unexpected { span: token.span });
+        }
+    }
+
+    if expect_operand {
+        return Err(FoldError::MissingOperand { span: end });
+    }
+    while let Some(top) = pending.pop() {
+        if let Pending::Open(open) = top {
+            return Err(FoldError::Unclosed { open: open.span });
+        }
+        reduce(&mut operands, top);
+    }
+    Ok(operands.pop().expect("folded expression"))
+}
+
+// reduce the pending operators that bind tighter than `next`
+fn unwind<T, E>(
+    operands: &mut Vec<WithSpan<E>>,
+    pending: &mut Vec<Pending<T>>,
+    next: Operator,
+    span: Span,
+    reduce: &mut impl FnMut(&mut Vec<WithSpan<E>>, Pending<T>),
+) -> Result<(), FoldError> {
+    loop {
+        let top = match pending.last() {
+            Some(Pending::Prefix(_, op)) | Some(Pending::Infix(_, op)) => *op,
+            _ => return Ok(()),
+        };
+        if top.precedence == next.precedence
+            && top.assoc == Assoc::None
+            && next.assoc == Assoc::None
+        {
+            return Err(FoldError::NonAssociative { span });
+        }
+        let binds_tighter = top.precedence > next.precedence
+            || (top.precedence == next.precedence && next.assoc != Assoc::Right);
+        if !binds_tighter {
+            return Ok(());
+        }
+        let top = pending.pop().expect("pending operator");
+        reduce(operands, top);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fold(source: &str) -> Result<WithSpan<alloc::string::String>, FoldError> {
+        let table = PrecedenceTable::new()
+            .infix('+', 1, Assoc::Left)
+            .infix('=', 0, Assoc::None)
+            .infix('^', 2, Assoc::Right)
+            .prefix('-', 3)
+            .postfix('!', 4);
+        let tokens = crate::testing::chars(source)
+            .into_iter()
+            .filter(|k| !k.item.is_whitespace());
+
+        fold_expr(
+            tokens,
+            &table,
+            &[('(', ')'), ('[', ']')],
+            |k| Some(k.item.to_string()).filter(|_| k.item.is_ascii_alphabetic()),
+            |op, a| format!("({}{})", op.item, a.item),
+            |a, op, b| format!("({} {} {})", a.item, op.item, b.item),
+        )
+    }
+
+    #[test]
+    fn precedence() {
+        let folded = |source| fold(source).map(|k| k.item);
+        assert_eq!(folded("a + b + c").unwrap(), "((a + b) + c)");
+        assert_eq!(folded("a ^ b ^ c").unwrap(), "(a ^ (b ^ c))");
+        assert_eq!(folded("-a ^ b!").unwrap(), "((-a) ^ (!b))");
+        assert_eq!(folded("a + [b + c]").unwrap(), "(a + (b + c))");
+        assert_eq!(folded("a = b + c").unwrap(), "(a = (b + c))");
+
+        let value = fold(" (a) + b").unwrap();
+        assert_eq!(value.span, Span::from(1..8));
+    }
+
+    #[test]
+    fn errors() {
+        let span = |start: usize, end: usize| Span::from(start..end);
+        assert_eq!(
+            fold("a = b = c"),
+            Err(FoldError::NonAssociative { span: span(6, 7) })
+        );
+        assert_eq!(
+            fold("a +"),
+            Err(FoldError::MissingOperand { span: span(3, 3) })
+        );
+        assert_eq!(
+            fold(""),
+            Err(FoldError::MissingOperand { span: span(0, 0) })
+        );
+        assert_eq!(fold("a b"), Err(FoldError::Unexpected { span: span(2, 3) }));
+        assert_eq!(fold("(a]"), Err(FoldError::Unexpected { span: span(2, 3) }));
+        assert_eq!(fold("a)"), Err(FoldError::Unexpected { span: span(1, 2) }));
+        assert_eq!(fold("+"), Err(FoldError::Unexpected { span: span(0, 1) }));
+        assert_eq!(
+            fold("[a + (b)"),
+            Err(FoldError::Unclosed { open: span(0, 1) })
+        );
+    }
+}
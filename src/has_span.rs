@@ -0,0 +1,120 @@
+use crate::{Attached, Expanded, Located, Span, WithSpan};
+use alloc::boxed::Box;
+
+/// `HasSpan` is something that covers a [`Span`](./struct.Span.html) of a source,
+/// like a token or a node of a syntax tree
+///
+/// This can be derived for structs and enums, where the span is the join of the
+/// spans of the fields. Fields that don't have a span are skipped with `#[span(skip)]`
+///
+/// ```rust
+/// use logos_iterator::{HasSpan, Span, WithSpan};
+///
+/// #[derive(HasSpan)]
+/// struct Binary {
+///     lhs: Box<Expr>,
+///     op: WithSpan<char>,
+///     rhs: Box<Expr>,
+/// }
+///
+/// #[derive(HasSpan)]
+/// enum Expr {
+///     Number(WithSpan<i32>),
+///     Binary(Binary),
+///     Named {
+///         #[span(skip)]
+///         name: String,
+///         span: Span,
+///     },
+/// }
+///
+/// let expr = Expr::Binary(Binary {
+///     lhs: Box::new(Expr::Number(WithSpan::new(1, Span::from(0..1)))),
+///     op: WithSpan::new('+', Span::from(2..3)),
+///     rhs: Box::new(Expr::Named { name: "a".into(), span: Span::from(4..5) }),
+/// });
+/// assert_eq!(expr.span(), Span::from(0..5));
+/// ```
+pub trait HasSpan {
+    /// The span that this covers
+    fn span(&self) -> Span;
+}
+
+pub use logos_iterator_derive::HasSpan;
+
+impl HasSpan for Span {
+    fn span(&self) -> Span {
+        *self
+    }
+}
+
+impl<T> HasSpan for WithSpan<T> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl<T> HasSpan for Located<T> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl<T> HasSpan for Expanded<T> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl<T> HasSpan for Attached<T> {
+    fn span(&self) -> Span {
+        Attached::span(self)
+    }
+}
+
+impl<T> HasSpan for &T
+where
+    T: HasSpan + ?Sized,
+{
+    fn span(&self) -> Span {
+        (**self).span()
+    }
+}
+
+impl<T> HasSpan for Box<T>
+where
+    T: HasSpan + ?Sized,
+{
+    fn span(&self) -> Span {
+        (**self).span()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(HasSpan)]
+    struct Call<'a>(WithSpan<&'a str>, #[span(skip)] usize, Box<Args>);
+
+    #[derive(HasSpan)]
+    enum Args {
+        One(Span),
+        Many { first: Span, rest: WithSpan<()> },
+    }
+
+    #[test]
+    fn derive() {
+        let many = Args::Many {
+            first: Span::from(4..5),
+            rest: WithSpan::new((), Span::from(7..9)),
+        };
+        assert_eq!(many.span(), Span::from(4..9));
+
+        let call = Call(WithSpan::new("f", Span::from(0..1)), 3, Box::new(many));
+        assert_eq!(call.span(), Span::from(0..9));
+        assert_eq!(call.1, 3);
+        assert_eq!(Args::One(Span::from(2..3)).span(), Span::from(2..3));
+        assert_eq!((&&call).span(), Span::from(0..9));
+    }
+}
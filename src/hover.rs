@@ -0,0 +1,134 @@
+use crate::{LineCol, LineIndex, Span, TokenStream, WithSpan};
+
+/// `Hover` is the token under a cursor, as found by [`hover_at`](./fn.hover_at.html)
+#[derive(Debug, PartialEq)]
+pub struct Hover<'a, T> {
+    /// The token
+    pub token: &'a WithSpan<T>,
+    /// The index of the token in the stream
+    pub index: usize,
+    /// The text of the token
+    pub text: &'a str,
+    /// Where the token starts
+    pub start: LineCol,
+    /// Where the token ends
+    pub end: LineCol,
+}
+
+impl<T> Hover<'_, T> {
+    /// The span of the token
+    pub fn span(&self) -> Span {
+        self.token.span
+    }
+}
+
+impl<T> Clone for Hover<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Hover<'_, T> {}
+
+/// Find the token of `stream` under the cursor at the byte `offset` of `source`
+///
+/// A cursor just after a token (at its `end`) hovers it, when no other token
+/// starts there, as editors put the cursor between characters. This returns
+/// `None` if the cursor isn't on a token, such as in skipped whitespace
+///
+/// This builds a [`LineIndex`](./struct.LineIndex.html) of the source, use
+/// [`hover_at_with`](./fn.hover_at_with.html) to reuse one
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, hover_at, LineCol, TokenStream};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Word,
+///     #[token = "\n"]
+///     NewLine,
+/// }
+///
+/// let source = "let\nfoo bar";
+/// let stream = lex::<Token, _>(source).spanned().collect::<TokenStream<_>>();
+///
+/// let hover = hover_at(source, &stream, 6).unwrap();
+/// assert_eq!(hover.token.item, Token::Word);
+/// assert_eq!(hover.text, "foo");
+/// assert_eq!((hover.start, hover.end), (LineCol { line: 2, column: 1 }, LineCol { line: 2, column: 4 }));
+///
+/// // just after `foo`
+/// assert_eq!(hover_at(source, &stream, 7).unwrap().text, "foo");
+/// assert!(hover_at(source, &stream, 20).is_none());
+/// ```
+pub fn hover_at<'a, T>(
+    source: &'a str,
+    stream: &'a TokenStream<T>,
+    offset: usize,
+) -> Option<Hover<'a, T>> {
+    hover_at_with(source, &LineIndex::new(source), stream, offset)
+}
+
+/// Find the token of `stream` under the cursor at the byte `offset` of `source`,
+/// using the `index` of the source for the positions
+///
+/// See [`hover_at`](./fn.hover_at.html)
+pub fn hover_at_with<'a, T>(
+    source: &'a str,
+    index: &LineIndex,
+    stream: &'a TokenStream<T>,
+    offset: usize,
+) -> Option<Hover<'a, T>> {
+    let (i, token) = stream.token_at(offset).or_else(|| {
+        stream
+            .token_before(offset)
+            .filter(|(_, k)| k.span.end == offset && k.span.start < offset)
+    })?;
+
+    Some(Hover {
+        token,
+        index: i,
+        text: source.get(token.span.start..token.span.end)?,
+        start: index.line_col(token.span.start),
+        end: index.line_col(token.span.end),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hover() {
+        let source = "ab  cd";
+        let stream = [(0, 2), (4, 6)]
+            .iter()
+            .map(|&(start, end)| WithSpan::new((), Span { start, end }))
+            .collect::<TokenStream<_>>();
+
+        let texts = (0..=7)
+            .map(|offset| hover_at(source, &stream, offset).map(|k| (k.index, k.text)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            vec![
+                Some((0, "ab")),
+                Some((0, "ab")),
+                Some((0, "ab")),
+                None,
+                Some((1, "cd")),
+                Some((1, "cd")),
+                Some((1, "cd")),
+                None,
+            ]
+        );
+
+        let empty = TokenStream::<()>::default();
+        assert!(hover_at("", &empty, 0).is_none());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::Span;
+use alloc::vec::Vec;
+
+/// `IndentWarning` is a problem found by [`check_indentation`](./fn.check_indentation.html)
+///
+/// The spans are of the leading whitespace of the line
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IndentWarning {
+    /// The indentation has both tabs and spaces
+    Mixed { span: Span },
+    /// The indentation uses a different character than the `first` indented line
+    Inconsistent { span: Span, first: Span },
+    /// The indentation increased by a different width than the first indented line
+    Width {
+        span: Span,
+        expected: usize,
+        found: usize,
+    },
+    /// The indentation decreased to a width that doesn't match an outer block
+    Dedent { span: Span },
+}
+
+impl core::fmt::Display for IndentWarning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Mixed { span } => write!(f, "tabs mixed with spaces at {}", span),
+            Self::Inconsistent { span, first } => write!(
+                f,
+                "indentation at {} is different from the indentation at {}",
+                span, first
+            ),
+            Self::Width {
+                span,
+                expected,
+                found,
+            } => write!(
+                f,
+                "indentation at {} is {} wide, expected {}",
+                span, found, expected
+            ),
+            Self::Dedent { span } => {
+                write!(f, "indentation at {} doesn't match an outer block", span)
+            }
+        }
+    }
+}
+
+/// Check the leading whitespace of each line of `source` for inconsistent indentation
+///
+/// Blank lines are ignored. Widths are counted in characters, and the first
+/// indented line decides both the indent character and the indent width.
+/// The width of a line with mixed indentation isn't checked
+///
+/// ```rust
+/// # use logos_iterator::{check_indentation, IndentWarning, Span};
+/// let source = "a\n  b\n    c\n   d\n\t\te\n";
+/// assert_eq!(
+///     check_indentation(source),
+///     vec![
+///         IndentWarning::Dedent { span: Span::from(12..15) },
+///         IndentWarning::Inconsistent { span: Span::from(17..19), first: Span::from(2..4) },
+///     ]
+/// );
+/// ```
+pub fn check_indentation(source: &str) -> Vec<IndentWarning> {
+    let mut warnings = vec![];
+    // (the indent character, the span of the first indented line)
+    let mut style: Option<(u8, Span)> = None;
+    let mut unit = None;
+    let mut levels = vec![0];
+
+    let mut start = 0;
+    for line in source.split_inclusive('\n') {
+        let offset = start;
+        start += line.len();
+
+        let width = line
+            .bytes()
+            .take_while(|&c| c == b' ' || c == b'\t')
+            .count();
+        if line[width..].trim().is_empty() {
+            continue;
+        }
+        let span = Span::from(offset..offset + width);
+        let indent = &line.as_bytes()[..width];
+
+        let mixed = indent.contains(&b' ') && indent.contains(&b'\t');
+        if mixed {
+            warnings.push(IndentWarning::Mixed { span })
+        } else if let Some(&c) = indent.first() {
+            match style {
+                Some((expected, first)) if expected != c => {
+                    warnings.push(IndentWarning::Inconsistent { span, first })
+                }
+                Some(..) => {}
+                None => style = Some((c, span)),
+            }
+        }
+
+        let current = *levels.last().unwrap();
+        if width > current {
+            let found = width - current;
+            match unit {
+                Some(expected) if expected != found && !mixed => {
+                    warnings.push(IndentWarning::Width {
+                        span,
+                        expected,
+                        found,
+                    })
+                }
+                Some(..) => {}
+                None => unit = Some(found),
+            }
+            levels.push(width);
+        } else if width < current {
+            while levels.last().is_some_and(|&level| level > width) {
+                levels.pop();
+            }
+            if levels.last() != Some(&width) {
+                warnings.push(IndentWarning::Dedent { span });
+                levels.push(width);
+            }
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_indentation() {
+        let source = "a\n    b\n\n    c\n        d\n  e\n\t \tf\n      g\nh\n";
+        assert_eq!(
+            super::check_indentation(source),
+            vec![
+                IndentWarning::Dedent {
+                    span: Span::from(25..27)
+                },
+                IndentWarning::Mixed {
+                    span: Span::from(29..32)
+                },
+                IndentWarning::Width {
+                    span: Span::from(34..40),
+                    expected: 4,
+                    found: 3
+                },
+            ]
+        );
+
+        assert!(super::check_indentation("a\n\tb\n\t\tc\n\td\n").is_empty());
+    }
+}
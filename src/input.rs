@@ -0,0 +1,91 @@
+use crate::{Span, SpannedLexer, WithSpan};
+
+/// `TokenInput` is a source of spanned tokens that a parser can look ahead into
+///
+/// This is implemented for [`SpannedLexer`](./type.SpannedLexer.html) and for
+/// [`Cursor`](./struct.Cursor.html) (so for a
+/// [`TokenStream`](./struct.TokenStream.html) with
+/// [`TokenStream::cursor`](./struct.TokenStream.html#method.cursor)), so a parser
+/// written against it can use either
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, Span, TokenInput, TokenStream};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Word,
+///     #[token = ","]
+///     Comma,
+/// }
+///
+/// // count the words in a comma separated list
+/// fn list(input: &mut impl TokenInput<Token = Token>) -> Result<usize, Span> {
+///     let mut count = 0;
+///     loop {
+///         match input.advance() {
+///             Some(k) if k.item == Token::Word => count += 1,
+///             _ => return Err(input.position_span()),
+///         }
+///         if input.peek().map(|k| *k.item) != Some(Token::Comma) {
+///             return Ok(count);
+///         }
+///         input.advance();
+///     }
+/// }
+///
+/// let source = "a, b, c";
+/// assert_eq!(list(&mut lex::<Token, _>(source).spanned()), Ok(3));
+///
+/// let stream = lex::<Token, _>(source).spanned().collect::<TokenStream<_>>();
+/// assert_eq!(list(&mut stream.cursor()), Ok(3));
+/// ```
+pub trait TokenInput {
+    /// The token that is yielded
+    type Token;
+
+    /// The next token, without consuming it
+    fn peek(&self) -> Option<WithSpan<&Self::Token>>;
+
+    /// Consume the next token
+    fn advance(&mut self) -> Option<WithSpan<Self::Token>>;
+
+    /// The span of the next token, or an empty span at the end of the input
+    fn position_span(&self) -> Span;
+
+    /// Whether all of the tokens have been consumed
+    fn is_at_end(&self) -> bool {
+        self.peek().is_none()
+    }
+}
+
+/// With [`merge_errors`](./struct.Lexer.html#method.merge_errors), peeking at a run of
+/// errors only shows the first of them, but advancing consumes the whole run
+impl<'a, T, S> TokenInput for SpannedLexer<T, S>
+where
+    T: Clone + PartialEq<T>,
+    T: ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'a>,
+{
+    type Token = T;
+
+    fn peek(&self) -> Option<WithSpan<&Self::Token>> {
+        let inner = self.inner();
+        if inner.token == T::END {
+            return None;
+        }
+        Some(WithSpan::new(&inner.token, Span::from(inner.range())))
+    }
+
+    fn advance(&mut self) -> Option<WithSpan<Self::Token>> {
+        self.next()
+    }
+
+    fn position_span(&self) -> Span {
+        Span::from(self.inner().range())
+    }
+}
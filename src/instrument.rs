@@ -0,0 +1,122 @@
+use crate::WithSpan;
+use std::time::{Duration, Instant};
+
+/// `Instrumented` records the throughput of an iterator of spanned tokens
+///
+/// This is created with [`TokenIteratorExt::instrumented`](./trait.TokenIteratorExt.html#method.instrumented).
+/// The elapsed time only counts the time spent producing tokens, and the bytes
+/// consumed are up to the end of the last token
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, TokenIteratorExt as _};
+/// # #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// # enum Token {
+/// #     #[end]
+/// #     Eof,
+/// #     #[error]
+/// #     Unknown,
+/// #     #[regex = "[a-z]+"]
+/// #     Word,
+/// # }
+/// let mut lexer = lex::<Token, _>("hello world").spanned().instrumented();
+/// lexer.by_ref().for_each(drop);
+/// assert_eq!(lexer.tokens(), 2);
+/// assert_eq!(lexer.bytes(), 11);
+/// println!("{:.2} tokens/s, {:.2} MB/s", lexer.tokens_per_sec(), lexer.mb_per_sec());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Instrumented<I> {
+    iter: I,
+    bytes: usize,
+    tokens: usize,
+    elapsed: Duration,
+}
+
+impl<I> Instrumented<I> {
+    pub(crate) fn new(iter: I) -> Self {
+        Self {
+            iter,
+            bytes: 0,
+            tokens: 0,
+            elapsed: Duration::default(),
+        }
+    }
+
+    /// The number of bytes consumed
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// The number of tokens produced
+    pub fn tokens(&self) -> usize {
+        self.tokens
+    }
+
+    /// The time spent producing tokens
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The number of tokens produced per second
+    pub fn tokens_per_sec(&self) -> f64 {
+        per_sec(self.tokens as f64, self.elapsed)
+    }
+
+    /// The number of megabytes consumed per second
+    pub fn mb_per_sec(&self) -> f64 {
+        per_sec(self.bytes as f64 / 1_000_000.0, self.elapsed)
+    }
+
+    /// Consume the wrapper, returning the inner iterator
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+}
+
+fn per_sec(n: f64, elapsed: Duration) -> f64 {
+    match elapsed.as_secs_f64() {
+        secs if secs > 0.0 => n / secs,
+        _ => 0.0,
+    }
+}
+
+impl<I, T> Iterator for Instrumented<I>
+where
+    I: Iterator<Item = WithSpan<T>>,
+{
+    type Item = WithSpan<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = Instant::now();
+        let next = self.iter.next();
+        self.elapsed += start.elapsed();
+
+        let token = next?;
+        self.tokens += 1;
+        self.bytes = self.bytes.max(token.span.end);
+        Some(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Span, TokenIteratorExt as _, WithSpan};
+
+    #[test]
+    fn instrumented() {
+        let tokens = vec![
+            WithSpan::new('a', Span::from(0..3)),
+            WithSpan::new('b', Span::from(4..10)),
+        ];
+        let mut iter = tokens.into_iter().instrumented();
+        assert_eq!((iter.tokens(), iter.bytes()), (0, 0));
+        assert_eq!(iter.tokens_per_sec(), 0.0);
+
+        iter.next();
+        assert_eq!((iter.tokens(), iter.bytes()), (1, 3));
+        iter.by_ref().for_each(drop);
+        assert_eq!((iter.tokens(), iter.bytes()), (2, 10));
+        assert!(iter.tokens_per_sec() >= 0.0);
+        assert_eq!(iter.into_inner().count(), 0);
+    }
+}
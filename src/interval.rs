@@ -0,0 +1,144 @@
+use crate::{Span, TokenStream};
+use alloc::vec::Vec;
+
+/// `IntervalIndex` answers queries over spans that can nest or overlap
+///
+/// This is useful for streams with synthetic spans (e.g. from expansions or
+/// grouping) where a binary search over the starts isn't enough.
+/// Queries return the indices of the spans in the order they were given
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IntervalIndex {
+    // sorted by span, as an implicit balanced tree with the middle as the root
+    nodes: Vec<Node>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Node {
+    span: Span,
+    index: usize,
+    // the largest end in this node's subtree
+    max_end: usize,
+}
+
+impl IntervalIndex {
+    /// Create an index over the `spans`
+    pub fn new(spans: impl IntoIterator<Item = Span>) -> Self {
+        let mut nodes = spans
+            .into_iter()
+            .enumerate()
+            .map(|(index, span)| Node {
+                span,
+                index,
+                max_end: span.end,
+            })
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|node| node.span);
+
+        fn build(nodes: &mut [Node]) -> usize {
+            if nodes.is_empty() {
+                return 0;
+            }
+            let mid = nodes.len() / 2;
+            let left = build(&mut nodes[..mid]);
+            let right = build(&mut nodes[mid + 1..]);
+            let node = &mut nodes[mid];
+            node.max_end = node.max_end.max(left).max(right);
+            node.max_end
+        }
+        build(&mut nodes);
+
+        Self { nodes }
+    }
+
+    /// The number of spans in the index
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the index has no spans
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Get the indices of all of the spans that contain the byte `offset`
+    pub fn all_containing(&self, offset: usize) -> Vec<usize> {
+        self.query(offset, offset.saturating_add(1))
+    }
+
+    /// Get the indices of all of the spans that overlap with `span`
+    ///
+    /// Spans that only touch (e.g. `1..2` and `2..3`) don't overlap
+    pub fn overlapping(&self, span: Span) -> Vec<usize> {
+        self.query(span.start, span.end)
+    }
+
+    fn query(&self, start: usize, end: usize) -> Vec<usize> {
+        fn visit(nodes: &[Node], start: usize, end: usize, out: &mut Vec<usize>) {
+            if nodes.is_empty() {
+                return;
+            }
+            let mid = nodes.len() / 2;
+            let node = nodes[mid];
+            if node.max_end <= start {
+                return;
+            }
+            visit(&nodes[..mid], start, end, out);
+            if node.span.start < end {
+                if start < node.span.end {
+                    out.push(node.index)
+                }
+                visit(&nodes[mid + 1..], start, end, out);
+            }
+        }
+
+        let mut out = vec![];
+        if start < end {
+            visit(&self.nodes, start, end, &mut out);
+        }
+        out.sort_unstable();
+        out
+    }
+}
+
+impl<T> TokenStream<T> {
+    /// Create an [`IntervalIndex`](./struct.IntervalIndex.html) over the spans in
+    /// this stream
+    pub fn interval_index(&self) -> IntervalIndex {
+        IntervalIndex::new(self.iter().map(|k| k.span))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_index() {
+        let spans = [
+            Span::from(0..10),
+            Span::from(1..4),
+            Span::from(2..3),
+            Span::from(5..9),
+            Span::from(6..7),
+            Span::from(9..12),
+        ];
+        let index = IntervalIndex::new(spans.iter().copied());
+
+        for offset in 0..13 {
+            let expected = (0..spans.len())
+                .filter(|&i| spans[i].start <= offset && offset < spans[i].end)
+                .collect::<Vec<_>>();
+            assert_eq!(index.all_containing(offset), expected, "{}", offset);
+        }
+
+        assert_eq!(index.all_containing(2), vec![0, 1, 2]);
+        assert_eq!(index.overlapping(Span::from(3..6)), vec![0, 1, 3]);
+        assert_eq!(index.overlapping(Span::from(10..20)), vec![5]);
+        assert!(index.overlapping(Span::from(12..20)).is_empty());
+        assert!(index.overlapping(Span::from(4..4)).is_empty());
+
+        let index = IntervalIndex::new(vec![Span::from(0..usize::MAX)]);
+        assert_eq!(index.all_containing(usize::MAX - 1), vec![0]);
+        assert!(index.all_containing(usize::MAX).is_empty());
+    }
+}
@@ -0,0 +1,490 @@
+use crate::{Span, WithSpan};
+
+/// `Island` is a token from an [`Islands`](./struct.Islands.html) lexer, tagged
+/// with the language that it came from
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Island<O, I> {
+    /// A token of the outer language
+    Outer(O),
+    /// A token of the inner (embedded) language
+    Inner(I),
+}
+
+impl<O, I> Island<O, I> {
+    /// Whether this is a token of the outer language
+    pub fn is_outer(&self) -> bool {
+        matches!(self, Self::Outer(..))
+    }
+
+    /// Whether this is a token of the inner language
+    pub fn is_inner(&self) -> bool {
+        matches!(self, Self::Inner(..))
+    }
+}
+
+enum State<O, I, S>
+where
+    O: ::logos::Logos,
+    I: ::logos::Logos,
+{
+    Outer(::logos::Lexer<O, S>),
+    Inner(::logos::Lexer<I, S>),
+    Done,
+}
+
+/// `Islands` lexes a source with an outer token `O` that has islands of an inner
+/// token `I` embedded in it, like the expressions in the holes of a template
+///
+/// It starts with `O`, and switches to `I` after the `enter` token of `O`, and back
+/// to `O` after the `exit` token of `I`. The boundary tokens are yielded with
+/// the language they belong to. The extras are converted when switching, so
+/// they need `Into` each other
+///
+/// This is created with [`islands`](./fn.islands.html)
+pub struct Islands<O, I, S>
+where
+    O: ::logos::Logos,
+    I: ::logos::Logos,
+{
+    state: State<O, I, S>,
+    enter: O,
+    exit: I,
+}
+
+/// Lex the source `S` with the outer token `O`, switching to islands of `I`
+/// between the `enter` and `exit` tokens
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{islands, Island, Span};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Text {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[^{]+"]
+///     Text,
+///     #[token = "{{"]
+///     Open,
+/// }
+///
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Expr {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Ident,
+///     #[token = "."]
+///     Dot,
+///     #[token = "}}"]
+///     Close,
+/// }
+///
+/// let tokens = islands("hi {{user.name}}!", Text::Open, Expr::Close)
+///     .map(|k| k.item)
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     tokens,
+///     vec![
+///         Island::Outer(Text::Text),
+///         Island::Outer(Text::Open),
+///         Island::Inner(Expr::Ident),
+///         Island::Inner(Expr::Dot),
+///         Island::Inner(Expr::Ident),
+///         Island::Inner(Expr::Close),
+///         Island::Outer(Text::Text),
+///     ]
+/// );
+/// ```
+pub fn islands<'a, O, I, S>(source: S, enter: O, exit: I) -> Islands<O, I, S>
+where
+    O: PartialEq + ::logos::Logos + ::logos::source::WithSource<S>,
+    I: ::logos::Logos,
+    S: ::logos::source::Source<'a>,
+{
+    Islands {
+        state: State::Outer(O::lexer(source)),
+        enter,
+        exit,
+    }
+}
+
+impl<O, I, S> Islands<O, I, S>
+where
+    O: ::logos::Logos,
+    I: ::logos::Logos,
+{
+    /// Whether the next token is in an island of the inner language
+    pub fn in_island(&self) -> bool {
+        matches!(self.state, State::Inner(..))
+    }
+}
+
+impl<'a, O, I, S> Iterator for Islands<O, I, S>
+where
+    O: Clone + PartialEq + ::logos::Logos + ::logos::source::WithSource<S>,
+    I: Clone + PartialEq + ::logos::Logos + ::logos::source::WithSource<S>,
+    O::Extras: Into<I::Extras>,
+    I::Extras: Into<O::Extras>,
+    S: ::logos::source::Source<'a>,
+{
+    type Item = WithSpan<Island<O, I>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (token, span, state) = match core::mem::replace(&mut self.state, State::Done) {
+            State::Outer(mut lexer) => {
+                if lexer.token == O::END {
+                    return None;
+                }
+                let (token, span) = (lexer.token.clone(), Span::from(lexer.range()));
+                let state = if token == self.enter {
+                    State::Inner(lexer.advance_as())
+                } else {
+                    lexer.advance();
+                    State::Outer(lexer)
+                };
+                (Island::Outer(token), span, state)
+            }
+            State::Inner(mut lexer) => {
+                if lexer.token == I::END {
+                    return None;
+                }
+                let (token, span) = (lexer.token.clone(), Span::from(lexer.range()));
+                let state = if token == self.exit {
+                    State::Outer(lexer.advance_as())
+                } else {
+                    lexer.advance();
+                    State::Inner(lexer)
+                };
+                (Island::Inner(token), span, state)
+            }
+            State::Done => return None,
+        };
+        self.state = state;
+        Some(WithSpan::new(token, span))
+    }
+}
+
+impl<'a, O, I, S> core::iter::FusedIterator for Islands<O, I, S>
+where
+    O: Clone + PartialEq + ::logos::Logos + ::logos::source::WithSource<S>,
+    I: Clone + PartialEq + ::logos::Logos + ::logos::source::WithSource<S>,
+    O::Extras: Into<I::Extras>,
+    I::Extras: Into<O::Extras>,
+    S: ::logos::source::Source<'a>,
+{
+}
+
+/// `TemplateToken` is a token from a [`TemplateLexer`](./struct.TemplateLexer.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TemplateToken<O, I> {
+    /// A token of the outer language
+    Outer(O),
+    /// The marker that opens an island
+    Open,
+    /// A token of the inner language
+    Inner(I),
+    /// The marker that closes an island
+    Close,
+}
+
+enum Region<'a, O, I>
+where
+    O: ::logos::Logos,
+    I: ::logos::Logos,
+{
+    // a lexer over the source from `offset`, up to the marker at `end`
+    Outer {
+        lexer: ::logos::Lexer<O, &'a str>,
+        offset: usize,
+        end: usize,
+    },
+    Inner {
+        lexer: ::logos::Lexer<I, &'a str>,
+        offset: usize,
+        end: usize,
+    },
+    Done,
+}
+
+/// `TemplateLexer` lexes a template, where the text between the `open` and
+/// `close` markers is lexed with the inner token `I`, and the rest with the
+/// outer token `O`
+///
+/// This is for formats like HTML with `{{ expressions }}`, or Markdown with
+/// code fences. Unlike [`Islands`](./struct.Islands.html), the markers are
+/// found in the text, so each language only sees its own part of the source,
+/// and the markers are yielded as [`TemplateToken::Open`](./enum.TemplateToken.html#variant.Open)
+/// and [`TemplateToken::Close`](./enum.TemplateToken.html#variant.Close).
+/// The first `close` after an `open` ends the island, even if it is in a
+/// string of the inner language. An unclosed island ends with the source
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{Span, TemplateLexer, TemplateToken};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Html {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "<[a-z]+>"]
+///     Open,
+///     #[regex = "</[a-z]+>"]
+///     Close,
+///     #[regex = "[a-z]+"]
+///     Text,
+/// }
+///
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Expr {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Ident,
+///     #[token = "|"]
+///     Pipe,
+/// }
+///
+/// let source = "<b>{{ name | upper }}</b>";
+/// let tokens = TemplateLexer::new(source, "{{", "}}")
+///     .map(|k| (k.item, &source[k.span]))
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     tokens,
+///     vec![
+///         (TemplateToken::Outer(Html::Open), "<b>"),
+///         (TemplateToken::Open, "{{"),
+///         (TemplateToken::Inner(Expr::Ident), "name"),
+///         (TemplateToken::Inner(Expr::Pipe), "|"),
+///         (TemplateToken::Inner(Expr::Ident), "upper"),
+///         (TemplateToken::Close, "}}"),
+///         (TemplateToken::Outer(Html::Close), "</b>"),
+///     ]
+/// );
+/// ```
+pub struct TemplateLexer<'a, O, I>
+where
+    O: ::logos::Logos,
+    I: ::logos::Logos,
+{
+    source: &'a str,
+    open: &'a str,
+    close: &'a str,
+    region: Region<'a, O, I>,
+}
+
+impl<'a, O, I> TemplateLexer<'a, O, I>
+where
+    O: ::logos::Logos + ::logos::source::WithSource<&'a str>,
+    I: ::logos::Logos + ::logos::source::WithSource<&'a str>,
+{
+    /// Create a lexer for the template `source`, with islands between the
+    /// `open` and `close` markers
+    ///
+    /// # Panics
+    /// This panics if either marker is empty
+    pub fn new(source: &'a str, open: &'a str, close: &'a str) -> Self {
+        assert!(
+            !open.is_empty() && !close.is_empty(),
+            "the markers can't be empty"
+        );
+        let mut this = Self {
+            source,
+            open,
+            close,
+            region: Region::Done,
+        };
+        this.region = this.outer(0);
+        this
+    }
+
+    fn end_of(&self, offset: usize, marker: &str) -> usize {
+        self.source[offset..]
+            .find(marker)
+            .map_or(self.source.len(), |i| offset + i)
+    }
+
+    fn outer(&self, offset: usize) -> Region<'a, O, I> {
+        let end = self.end_of(offset, self.open);
+        Region::Outer {
+            lexer: O::lexer(&self.source[offset..end]),
+            offset,
+            end,
+        }
+    }
+
+    fn inner(&self, offset: usize) -> Region<'a, O, I> {
+        let end = self.end_of(offset, self.close);
+        Region::Inner {
+            lexer: I::lexer(&self.source[offset..end]),
+            offset,
+            end,
+        }
+    }
+}
+
+impl<'a, O, I> Iterator for TemplateLexer<'a, O, I>
+where
+    O: Clone + PartialEq + ::logos::Logos + ::logos::source::WithSource<&'a str>,
+    I: Clone + PartialEq + ::logos::Logos + ::logos::source::WithSource<&'a str>,
+{
+    type Item = WithSpan<TemplateToken<O, I>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset_span = |offset: usize, range: core::ops::Range<usize>| {
+            Span::from(offset + range.start..offset + range.end)
+        };
+
+        loop {
+            let (next, marker) = match &mut self.region {
+                Region::Outer { lexer, offset, end } => {
+                    if lexer.token != O::END {
+                        let token = TemplateToken::Outer(lexer.token.clone());
+                        let span = offset_span(*offset, lexer.range());
+                        lexer.advance();
+                        return Some(WithSpan::new(token, span));
+                    }
+                    if *end == self.source.len() {
+                        (Region::Done, None)
+                    } else {
+                        let after = *end + self.open.len();
+                        let span = Span::from(*end..after);
+                        (
+                            self.inner(after),
+                            Some(WithSpan::new(TemplateToken::Open, span)),
+                        )
+                    }
+                }
+                Region::Inner { lexer, offset, end } => {
+                    if lexer.token != I::END {
+                        let token = TemplateToken::Inner(lexer.token.clone());
+                        let span = offset_span(*offset, lexer.range());
+                        lexer.advance();
+                        return Some(WithSpan::new(token, span));
+                    }
+                    if *end == self.source.len() {
+                        (Region::Done, None)
+                    } else {
+                        let after = *end + self.close.len();
+                        let span = Span::from(*end..after);
+                        (
+                            self.outer(after),
+                            Some(WithSpan::new(TemplateToken::Close, span)),
+                        )
+                    }
+                }
+                Region::Done => return None,
+            };
+            self.region = next;
+            if marker.is_some() {
+                return marker;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Markup {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+        #[token = "`"]
+        Tick,
+    }
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Code {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[0-9]+"]
+        Number,
+        #[token = "+"]
+        Plus,
+        #[token = "`"]
+        Tick,
+    }
+
+    #[test]
+    fn switch() {
+        let source = "add `1+2` and `3";
+        let mut lexer = islands(source, Markup::Tick, Code::Tick);
+        let mut tokens = vec![];
+        while let Some(token) = lexer.next() {
+            tokens.push((token.item, &source[token.span], lexer.in_island()));
+        }
+
+        use Island::*;
+        assert_eq!(
+            tokens,
+            vec![
+                (Outer(Markup::Word), "add", false),
+                (Outer(Markup::Tick), "`", true),
+                (Inner(Code::Number), "1", true),
+                (Inner(Code::Plus), "+", true),
+                (Inner(Code::Number), "2", true),
+                (Inner(Code::Tick), "`", false),
+                (Outer(Markup::Word), "and", false),
+                (Outer(Markup::Tick), "`", true),
+                // an unclosed island ends with the source
+                (Inner(Code::Number), "3", true),
+            ]
+        );
+        assert!(lexer.next().is_none());
+        assert!(tokens.iter().all(|(k, ..)| k.is_outer() != k.is_inner()));
+    }
+
+    #[test]
+    fn template() {
+        let source = "a ```1+2``` b ```3";
+        let tokens = TemplateLexer::<Markup, Code>::new(source, "```", "```")
+            .map(|k| (k.item, &source[k.span]))
+            .collect::<Vec<_>>();
+
+        use TemplateToken::*;
+        assert_eq!(
+            tokens,
+            vec![
+                (Outer(Markup::Word), "a"),
+                (Open, "```"),
+                (Inner(Code::Number), "1"),
+                (Inner(Code::Plus), "+"),
+                (Inner(Code::Number), "2"),
+                (Close, "```"),
+                (Outer(Markup::Word), "b"),
+                (Open, "```"),
+                (Inner(Code::Number), "3"),
+            ]
+        );
+
+        // empty regions, and markers at the ends of the source
+        let tokens = TemplateLexer::<Markup, Code>::new("{}{x}", "{", "}")
+            .map(|k| (k.item, k.span))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec![
+                (Open, Span::from(0..1)),
+                (Close, Span::from(1..2)),
+                (Open, Span::from(2..3)),
+                (Inner(Code::Unknown), Span::from(3..4)),
+                (Close, Span::from(4..5)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,442 @@
+use crate::WithSpan;
+
+/// An extension trait for iterators of spanned tokens
+///
+/// This is implemented for every iterator that yields
+/// [`WithSpan`](./struct.WithSpan.html), such as a
+/// [`SpannedLexer`](./type.SpannedLexer.html)
+pub trait TokenIteratorExt<T>: Iterator<Item = WithSpan<T>> + Sized {
+    /// Merge runs of the same token, for tokens where `kind` returns `true`, into
+    /// a single token with a span covering the run
+    ///
+    /// ```rust
+    /// # use logos::Logos;
+    /// # use logos_iterator::{lex, Span, TokenIteratorExt as _};
+    /// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    /// enum Token {
+    ///     #[end]
+    ///     Eof,
+    ///     #[error]
+    ///     Unknown,
+    ///     #[regex = "[a-z]+"]
+    ///     Word,
+    ///     #[token = "\n"]
+    ///     NewLine,
+    /// }
+    ///
+    /// let tokens = lex::<Token, _>("a\n\n\nb\n")
+    ///     .spanned()
+    ///     .coalesce(|k| *k == Token::NewLine)
+    ///     .map(|k| (k.item, k.span))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(tokens, vec![
+    ///     (Token::Word, Span::from(0..1)),
+    ///     (Token::NewLine, Span::from(1..4)),
+    ///     (Token::Word, Span::from(4..5)),
+    ///     (Token::NewLine, Span::from(5..6)),
+    /// ]);
+    /// ```
+    fn coalesce<F>(self, kind: F) -> Coalesce<Self, T, F>
+    where
+        T: PartialEq,
+        F: FnMut(&T) -> bool,
+    {
+        Coalesce {
+            iter: self,
+            pending: None,
+            kind,
+        }
+    }
+
+    /// Pair each token with its nesting depth, for the bracket `pairs` of `(open, close)` kinds
+    ///
+    /// Openers and closers have the depth outside of them. A stray closer doesn't
+    /// go below zero
+    ///
+    /// ```rust
+    /// # use logos_iterator::{Span, TokenIteratorExt as _, WithSpan};
+    /// let depths = "a(b[c])d"
+    ///     .char_indices()
+    ///     .map(|(i, c)| WithSpan::new(c, Span::from(i..i + 1)))
+    ///     .with_depth(&[('(', ')'), ('[', ']')])
+    ///     .map(|(_, depth)| depth)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(depths, vec![0, 0, 1, 1, 2, 1, 0, 0]);
+    /// ```
+    fn with_depth(self, pairs: &[(T, T)]) -> WithDepth<'_, Self, T>
+    where
+        T: PartialEq,
+    {
+        WithDepth {
+            iter: self,
+            pairs,
+            depth: 0,
+        }
+    }
+
+    /// Yield the regions that the lexer skipped as `kind` tokens, between the tokens
+    ///
+    /// The lexer skips input (like whitespace) without producing tokens, so the
+    /// spans jump over it. With this, every byte of a source of `source_len`
+    /// bytes is covered by exactly one span, in order
+    ///
+    /// ```rust
+    /// # use logos::Logos;
+    /// # use logos_iterator::{lex, TokenIteratorExt as _};
+    /// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    /// enum Token {
+    ///     #[end]
+    ///     Eof,
+    ///     #[error]
+    ///     Unknown,
+    ///     #[regex = "[a-z]+"]
+    ///     Word,
+    ///     Skipped,
+    /// }
+    ///
+    /// let source = "  ab cd ";
+    /// let tokens = lex::<Token, _>(source)
+    ///     .spanned()
+    ///     .with_skipped(Token::Skipped, source.len())
+    ///     .map(|k| (k.item, &source[k.span]))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(tokens, vec![
+    ///     (Token::Skipped, "  "),
+    ///     (Token::Word, "ab"),
+    ///     (Token::Skipped, " "),
+    ///     (Token::Word, "cd"),
+    ///     (Token::Skipped, " "),
+    /// ]);
+    /// ```
+    fn with_skipped(self, kind: T, source_len: usize) -> WithSkipped<Self, T>
+    where
+        T: Clone,
+    {
+        WithSkipped {
+            iter: self,
+            kind,
+            source_len,
+            pos: 0,
+            pending: None,
+        }
+    }
+
+    /// Pair each token with its slice of the `source`
+    ///
+    /// This panics if a span isn't in the source, use
+    /// [`checked_slices_in`](#method.checked_slices_in) for tokens that
+    /// might not come from it. This works on any spanned tokens, while
+    /// [`Lexer::with_slices`](./struct.Lexer.html#method.with_slices) lexes
+    /// them with their slices
+    ///
+    /// ```rust
+    /// # use logos_iterator::{Span, TokenIteratorExt as _, WithSpan};
+    /// let source = "ab cd";
+    /// let slices = vec![WithSpan::new(1, Span::from(0..2)), WithSpan::new(2, Span::from(3..5))]
+    ///     .into_iter()
+    ///     .slices_in(source)
+    ///     .map(|(k, slice)| (k.item, slice))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(slices, vec![(1, "ab"), (2, "cd")]);
+    /// ```
+    fn slices_in(self, source: &str) -> SlicesIn<'_, Self> {
+        SlicesIn { iter: self, source }
+    }
+
+    /// Pair each token with its slice of the `source`, or `None` if its span
+    /// isn't in the source (or isn't on a `char` boundary)
+    ///
+    /// This is for tokens that synthetic passes produced, whose spans might be made up
+    fn checked_slices_in(self, source: &str) -> CheckedSlicesIn<'_, Self> {
+        CheckedSlicesIn { iter: self, source }
+    }
+
+    /// Record [`TokenStats`](./struct.TokenStats.html) of the tokens as they are yielded
+    fn record_stats(self, stats: &mut crate::TokenStats<T>) -> crate::RecordStats<'_, Self, T>
+    where
+        T: Clone + PartialEq + ::logos::Logos,
+    {
+        crate::RecordStats { iter: self, stats }
+    }
+
+    /// Record the throughput of this iterator, as an [`Instrumented`](./struct.Instrumented.html)
+    #[cfg(feature = "std")]
+    fn instrumented(self) -> crate::Instrumented<Self> {
+        crate::Instrumented::new(self)
+    }
+}
+
+impl<I, T> TokenIteratorExt<T> for I where I: Iterator<Item = WithSpan<T>> {}
+
+/// An iterator that merges runs of the same token
+///
+/// This is created with [`TokenIteratorExt::coalesce`](./trait.TokenIteratorExt.html#method.coalesce)
+pub struct Coalesce<I, T, F> {
+    iter: I,
+    pending: Option<WithSpan<T>>,
+    kind: F,
+}
+
+impl<I, T, F> Iterator for Coalesce<I, T, F>
+where
+    I: Iterator<Item = WithSpan<T>>,
+    T: PartialEq,
+    F: FnMut(&T) -> bool,
+{
+    type Item = WithSpan<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut current = self.pending.take().or_else(|| self.iter.next())?;
+        if !(self.kind)(&current.item) {
+            return Some(current);
+        }
+
+        for next in &mut self.iter {
+            if next.item != current.item {
+                self.pending.replace(next);
+                break;
+            }
+            current.span = current.span.join(next.span);
+        }
+        Some(current)
+    }
+}
+
+/// An iterator that pairs tokens with their nesting depth
+///
+/// This is created with [`TokenIteratorExt::with_depth`](./trait.TokenIteratorExt.html#method.with_depth)
+pub struct WithDepth<'a, I, T> {
+    iter: I,
+    pairs: &'a [(T, T)],
+    depth: usize,
+}
+
+impl<I, T> WithDepth<'_, I, T> {
+    /// The depth after the tokens yielded so far
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl<I, T> Iterator for WithDepth<'_, I, T>
+where
+    I: Iterator<Item = WithSpan<T>>,
+    T: PartialEq,
+{
+    type Item = (WithSpan<T>, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.iter.next()?;
+        if self.pairs.iter().any(|(open, _)| *open == token.item) {
+            self.depth += 1;
+            return Some((token, self.depth - 1));
+        }
+        if self.pairs.iter().any(|(_, close)| *close == token.item) {
+            self.depth = self.depth.saturating_sub(1);
+        }
+        Some((token, self.depth))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// An iterator that pairs tokens with their slice of a source
+///
+/// This is created with [`TokenIteratorExt::slices_in`](./trait.TokenIteratorExt.html#method.slices_in)
+pub struct SlicesIn<'a, I> {
+    iter: I,
+    source: &'a str,
+}
+
+impl<'a, I, T> Iterator for SlicesIn<'a, I>
+where
+    I: Iterator<Item = WithSpan<T>>,
+{
+    type Item = (WithSpan<T>, &'a str);
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.iter.next()?;
+        let slice = &self.source[token.span];
+        Some((token, slice))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// An iterator that pairs tokens with their slice of a source, if it has one
+///
+/// This is created with [`TokenIteratorExt::checked_slices_in`](./trait.TokenIteratorExt.html#method.checked_slices_in)
+pub struct CheckedSlicesIn<'a, I> {
+    iter: I,
+    source: &'a str,
+}
+
+impl<'a, I, T> Iterator for CheckedSlicesIn<'a, I>
+where
+    I: Iterator<Item = WithSpan<T>>,
+{
+    type Item = (WithSpan<T>, Option<&'a str>);
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.iter.next()?;
+        let slice = self.source.get(token.span.start..token.span.end);
+        Some((token, slice))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// An iterator that yields the skipped regions between tokens
+///
+/// This is created with [`TokenIteratorExt::with_skipped`](./trait.TokenIteratorExt.html#method.with_skipped)
+pub struct WithSkipped<I, T> {
+    iter: I,
+    kind: T,
+    source_len: usize,
+    pos: usize,
+    pending: Option<WithSpan<T>>,
+}
+
+impl<I, T> Iterator for WithSkipped<I, T>
+where
+    I: Iterator<Item = WithSpan<T>>,
+    T: Clone,
+{
+    type Item = WithSpan<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = match self.pending.take().or_else(|| self.iter.next()) {
+            Some(token) => token,
+            None if self.pos < self.source_len => {
+                let span = crate::Span::from(self.pos..self.source_len);
+                self.pos = self.source_len;
+                return Some(WithSpan::new(self.kind.clone(), span));
+            }
+            None => return None,
+        };
+
+        if self.pos < token.span.start {
+            let span = crate::Span::from(self.pos..token.span.start);
+            self.pos = token.span.start;
+            self.pending.replace(token);
+            return Some(WithSpan::new(self.kind.clone(), span));
+        }
+        self.pos = self.pos.max(token.span.end);
+        Some(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Span;
+
+    #[derive(logos::Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-zé]+"]
+        Word,
+        #[token = "\n"]
+        NewLine,
+        Skipped,
+    }
+
+    #[test]
+    fn with_skipped() {
+        for source in &["", "   ", "a", " é  b\n\t c ", "ab \n\n  éé"] {
+            let tokens = crate::lex::<Token, _>(*source)
+                .spanned()
+                .with_skipped(Token::Skipped, source.len())
+                .collect::<Vec<_>>();
+
+            // the spans cover all of the source, in order
+            let mut pos = 0;
+            for token in &tokens {
+                assert_eq!(token.span.start, pos, "{:?} in {:?}", token, source);
+                assert!(token.span.end > token.span.start);
+                pos = token.span.end;
+            }
+            assert_eq!(pos, source.len());
+
+            // and only whitespace was skipped
+            let skipped = tokens
+                .iter()
+                .filter(|k| k.item == Token::Skipped)
+                .map(|k| &source[k.span])
+                .collect::<String>();
+            assert!(skipped.chars().all(char::is_whitespace), "{:?}", skipped);
+        }
+    }
+
+    // a token that carries data
+    #[derive(PartialEq, Clone, Debug)]
+    enum Value {
+        Word(alloc::string::String),
+        Gap,
+    }
+
+    #[test]
+    fn payloads() {
+        let source = "ab ab  cd";
+        let tokens = crate::lex::<Token, _>(source)
+            .spanned()
+            .map(|k| WithSpan::new(Value::Word(source[k.span].into()), k.span))
+            .with_skipped(Value::Gap, source.len())
+            .coalesce(|k| matches!(k, Value::Word(..)))
+            .with_depth(&[])
+            .map(|(k, _)| (k.item, k.span))
+            .collect::<Vec<_>>();
+
+        let word = |s: &str| Value::Word(s.into());
+        assert_eq!(
+            tokens,
+            vec![
+                (word("ab"), Span::from(0..2)),
+                (Value::Gap, Span::from(2..3)),
+                (word("ab"), Span::from(3..5)),
+                (Value::Gap, Span::from(5..7)),
+                (word("cd"), Span::from(7..9)),
+            ]
+        );
+    }
+
+    #[test]
+    fn checked_slices_in() {
+        let source = "aé";
+        let slices = [(0, 1), (1, 2), (1, 3), (3, 4), (2, 1)]
+            .iter()
+            .map(|&(start, end)| WithSpan::new((), Span { start, end }))
+            .checked_slices_in(source)
+            .map(|(_, slice)| slice)
+            .collect::<Vec<_>>();
+        assert_eq!(slices, vec![Some("a"), None, Some("é"), None, None]);
+    }
+
+    #[test]
+    fn slices_in() {
+        let source = "ab\ncd";
+        let slices = crate::lex::<Token, _>(source)
+            .spanned()
+            .slices_in(source)
+            .map(|(_, slice)| slice)
+            .collect::<Vec<_>>();
+        assert_eq!(slices, vec!["ab", "\n", "cd"]);
+    }
+
+    #[test]
+    fn with_depth() {
+        let mut iter = crate::testing::chars("{}}{{x")
+            .into_iter()
+            .with_depth(&[('{', '}')]);
+
+        let depths = iter.by_ref().map(|(_, depth)| depth).collect::<Vec<_>>();
+        assert_eq!(depths, vec![0, 0, 0, 0, 1, 2]);
+        assert_eq!(iter.depth(), 2);
+    }
+}
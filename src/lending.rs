@@ -0,0 +1,144 @@
+use crate::{Span, WithSpan};
+use core::marker::PhantomData;
+
+/// An iterator whose items can borrow from the iterator itself
+///
+/// Only one item can be held at a time, the next call to
+/// [`next`](#tymethod.next) needs the previous item to have been dropped
+pub trait LendingIterator {
+    /// The item that is yielded, borrowing from the iterator for `'a`
+    type Item<'a>
+    where
+        Self: 'a;
+
+    /// Advance the iterator and lend the next item
+    fn next(&mut self) -> Option<Self::Item<'_>>;
+
+    /// Call `f` with each item, consuming the iterator
+    fn for_each<F>(mut self, mut f: F)
+    where
+        Self: Sized,
+        F: FnMut(Self::Item<'_>),
+    {
+        while let Some(item) = self.next() {
+            f(item)
+        }
+    }
+}
+
+/// A lexer that lends each token, along with its slice of the source
+///
+/// The token is borrowed from the underlying `logos::Lexer` instead of being
+/// copied out of it, so `T` doesn't have to be `Clone`
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{LendingIterator as _, LendingLexer};
+/// // no Clone or Copy
+/// #[derive(Logos, PartialEq, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Word,
+/// }
+///
+/// let mut words = vec![];
+/// LendingLexer::<Token, _>::new("ab cd").for_each(|(token, slice)| {
+///     assert_eq!(*token.item, Token::Word);
+///     words.push(slice);
+/// });
+/// assert_eq!(words, vec!["ab", "cd"]);
+/// ```
+pub struct LendingLexer<'a, T, S>
+where
+    T: PartialEq<T> + ::logos::Logos,
+{
+    inner: ::logos::Lexer<T, S>,
+    started: bool,
+    source: PhantomData<&'a ()>,
+}
+
+impl<'a, T, S> LendingLexer<'a, T, S>
+where
+    T: PartialEq<T> + ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'a>,
+{
+    /// Create a new lexer from the source `S`
+    pub fn new(s: S) -> Self {
+        Self {
+            inner: T::lexer(s),
+            started: false,
+            source: PhantomData,
+        }
+    }
+
+    /// Get a reference to the underlying `logos::Lexer`
+    pub fn inner(&self) -> &::logos::Lexer<T, S> {
+        &self.inner
+    }
+}
+
+impl<'a, T, S> LendingIterator for LendingLexer<'a, T, S>
+where
+    T: PartialEq<T> + ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'a>,
+{
+    type Item<'b>
+        = (WithSpan<&'b T>, S::Slice)
+    where
+        Self: 'b;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        // the lexer is already on the first token, so only move on after it
+        if self.started {
+            self.inner.advance();
+        }
+        self.started = true;
+
+        if self.inner.token == T::END {
+            return None;
+        }
+        let span = Span::from(self.inner.range());
+        Some((WithSpan::new(&self.inner.token, span), self.inner.slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+    }
+
+    #[test]
+    fn lend() {
+        let mut lexer = LendingLexer::<Token, _>::new("ab $ cd");
+        let mut seen = vec![];
+        while let Some((token, slice)) = lexer.next() {
+            seen.push((token.span, slice, *token.item == Token::Word));
+        }
+        assert_eq!(
+            seen,
+            vec![
+                (Span::from(0..2), "ab", true),
+                (Span::from(3..4), "$", false),
+                (Span::from(5..7), "cd", true),
+            ]
+        );
+
+        // stays at the end
+        assert!(lexer.next().is_none());
+        assert!(LendingLexer::<Token, _>::new(&b""[..]).next().is_none());
+    }
+}
@@ -0,0 +1,493 @@
+use crate::{Span, WithSpan};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+/// Create a [`Lexer`](./struct.Lexer.html) over the source `S` for the token `T`
+///
+/// This yields plain tokens, use [`Lexer::spanned`](./struct.Lexer.html#method.spanned)
+/// or [`Lexer::with_slices`](./struct.Lexer.html#method.with_slices) to change
+/// what is yielded
+pub fn lex<'a, T, S>(s: S) -> Lexer<T, S>
+where
+    T: PartialEq<T> + ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'a>,
+{
+    Lexer::new(s)
+}
+
+/// Lex all of the source `S` into a `Vec` of spanned tokens
+///
+/// This reserves room for one token for every 4 bytes of the source, up to
+/// [`MAX_RESERVED_TOKENS`](./constant.MAX_RESERVED_TOKENS.html). Use
+/// [`lex_to_vec_with_hint`](./fn.lex_to_vec_with_hint.html) to change that
+///
+/// Errors aren't merged
+pub fn lex_to_vec<'a, T, S>(s: S) -> Vec<WithSpan<T>>
+where
+    T: Clone + PartialEq<T> + ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'a>,
+{
+    lex_to_vec_with_hint(s, 4)
+}
+
+/// The most tokens that [`lex_to_vec`](./fn.lex_to_vec.html) and
+/// [`Lexer::next_batch`](./struct.Lexer.html#method.next_batch) reserve room for up front
+///
+/// The `Vec` grows as usual past this, so a large source doesn't reserve
+/// much more memory than its tokens need
+pub const MAX_RESERVED_TOKENS: usize = 64 * 1024;
+
+/// Lex all of the source `S` into a `Vec` of spanned tokens, reserving room
+/// for one token for every `bytes_per_token` bytes of the source, up to
+/// [`MAX_RESERVED_TOKENS`](./constant.MAX_RESERVED_TOKENS.html)
+pub fn lex_to_vec_with_hint<'a, T, S>(s: S, bytes_per_token: usize) -> Vec<WithSpan<T>>
+where
+    T: Clone + PartialEq<T> + ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'a>,
+{
+    let mut lexer = T::lexer(s);
+    let hint = lexer.source.len() / bytes_per_token.max(1);
+    let mut tokens = Vec::with_capacity(hint.min(MAX_RESERVED_TOKENS));
+    while lexer.token != T::END {
+        tokens.push(WithSpan::new(
+            lexer.token.clone(),
+            Span::from(lexer.range()),
+        ));
+        lexer.advance();
+    }
+    tokens
+}
+
+/// A lexer that is an iterator over an input source, `S` that yields token `T` until
+/// the
+/// [`#[logos::end]`](https://docs.rs/logos/latest/logos/trait.Logos.html#associatedconstant.END)
+/// token is found
+///
+/// What is yielded is decided by the [`Mode`](./trait.Mode.html) `M`:
+/// * [`Tokens`](./struct.Tokens.html) yields just the token (the default)
+/// * [`Spanned`](./struct.Spanned.html) yields the token wrapped with a [`Span`](./struct.Span.html)
+/// * [`Sliced`](./struct.Sliced.html) yields the spanned token along with its slice of the source
+pub struct Lexer<T, S, M = Tokens>
+where
+    T: PartialEq<T> + ::logos::Logos,
+{
+    inner: ::logos::Lexer<T, S>,
+    merge_errors: bool,
+    mode: PhantomData<M>,
+}
+
+/// A lexer that is an iterator over an input source, `S` that yields token `T` until
+/// the
+/// [`#[logos::end]`](https://docs.rs/logos/latest/logos/trait.Logos.html#associatedconstant.END)
+/// token is found
+///
+/// The yielded element is wrapped with a [`Span`](./struct.Span.html), which is
+/// the byte offset into the source that the token is located at
+pub type SpannedLexer<T, S> = Lexer<T, S, Spanned>;
+
+impl<'a, T, S, M> Lexer<T, S, M>
+where
+    T: PartialEq<T> + ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'a>,
+{
+    /// Create a new lexer from the source `S`
+    ///
+    /// * T is the Token (that
+    ///   [`#[derive(Logos)]`](https://docs.rs/logos/latest/logos/trait.Logos.html)
+    ///   applies to)
+    /// * S is something that implements
+    ///   [`logos::Source`](https://docs.rs/logos/latest/logos/source/trait.Source.html)
+    ///   (`&str`, `&[u8]`, etc)
+    pub fn new(s: S) -> Self {
+        Self {
+            inner: T::lexer(s),
+            merge_errors: false,
+            mode: PhantomData,
+        }
+    }
+}
+
+impl<T, S, M> Lexer<T, S, M>
+where
+    T: PartialEq<T> + ::logos::Logos,
+{
+    /// Yield just the tokens
+    pub fn tokens(self) -> Lexer<T, S, Tokens> {
+        self.into_mode()
+    }
+
+    /// Yield the tokens wrapped with their [`Span`](./struct.Span.html)
+    pub fn spanned(self) -> Lexer<T, S, Spanned> {
+        self.into_mode()
+    }
+
+    /// Yield the spanned tokens along with their slice of the source
+    pub fn with_slices(self) -> Lexer<T, S, Sliced> {
+        self.into_mode()
+    }
+
+    /// Merge runs of adjacent
+    /// [`#[logos::error]`](https://docs.rs/logos/latest/logos/trait.Logos.html#associatedconstant.ERROR)
+    /// tokens into a single error token
+    ///
+    /// The merged token's span (and slice) covers the whole run
+    pub fn merge_errors(mut self) -> Self {
+        self.merge_errors = true;
+        self
+    }
+
+    /// Get a reference to the underlying `logos::Lexer`
+    pub fn inner(&self) -> &::logos::Lexer<T, S> {
+        &self.inner
+    }
+
+    /// Consume this, returning the underlying `logos::Lexer`
+    pub fn into_inner(self) -> ::logos::Lexer<T, S> {
+        self.inner
+    }
+
+    fn into_mode<N>(self) -> Lexer<T, S, N> {
+        Lexer {
+            inner: self.inner,
+            merge_errors: self.merge_errors,
+            mode: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, S, M> Lexer<T, S, M>
+where
+    T: Clone + PartialEq<T>,
+    T: ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'a>,
+    M: Mode<T, S>,
+{
+    /// Push up to `n` items into `buf`, returning how many were pushed
+    ///
+    /// This reserves room in `buf` once for the batch, and fills it straight
+    /// from the logos lexer. Fewer than `n` items are pushed only when the end
+    /// of the source is reached, so `usize::MAX` lexes the rest of the source
+    pub fn next_batch(&mut self, buf: &mut Vec<M::Item>, n: usize) -> usize {
+        // every token is at least a byte long, so there can't be more tokens than bytes left
+        let left = self.inner.source.len() - self.inner.range().start;
+        buf.reserve(n.min(left).min(MAX_RESERVED_TOKENS));
+
+        let mut pushed = 0;
+        while pushed < n {
+            let (token, span) = match self.step() {
+                Some(next) => next,
+                None => break,
+            };
+            buf.push(M::item(&self.inner, token, span));
+            pushed += 1;
+        }
+        pushed
+    }
+}
+
+impl<'a, T, S, M> Iterator for Lexer<T, S, M>
+where
+    T: Clone + PartialEq<T>,
+    T: ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'a>,
+    M: Mode<T, S>,
+{
+    type Item = M::Item;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (token, span) = self.step()?;
+        Some(M::item(&self.inner, token, span))
+    }
+}
+
+impl<'a, T, S, M> Lexer<T, S, M>
+where
+    T: Clone + PartialEq<T>,
+    T: ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'a>,
+    M: Mode<T, S>,
+{
+    #[inline]
+    fn step(&mut self) -> Option<(T, Span)> {
+        let token = self.inner.token.clone();
+        if token == T::END {
+            return None;
+        }
+
+        if token == T::ERROR && self.merge_errors {
+            let mut span = Span::from(self.inner.range());
+            self.inner.advance();
+            while self.inner.token == T::ERROR {
+                let range = self.inner.range();
+                if range.start != span.end {
+                    break;
+                }
+                span.end = range.end;
+                self.inner.advance();
+            }
+            debug_assert!(
+                span.end <= self.inner.source.len(),
+                "span {} is outside of the source",
+                span
+            );
+            return Some((token, span));
+        }
+
+        // only read the range if the mode will use it
+        let span = if M::USES_SPAN {
+            let range = self.inner.range();
+            Span::new(range.start, range.end)
+        } else {
+            Span::empty(0)
+        };
+        debug_assert!(
+            span.end <= self.inner.source.len(),
+            "span {} is outside of the source",
+            span
+        );
+        self.inner.advance();
+        Some((token, span))
+    }
+}
+
+/// A `Mode` decides what a [`Lexer`](./struct.Lexer.html) yields for a token
+pub trait Mode<T, S>
+where
+    T: ::logos::Logos,
+{
+    /// The item that is yielded
+    type Item;
+    /// Whether [`item`](#tymethod.item) uses the span
+    ///
+    /// When this is `false`, the span given to `item` is empty
+    const USES_SPAN: bool = true;
+    /// Produce the item for the `token` at `span`
+    ///
+    /// The `lexer` has already moved past this token, so only its source should be used
+    fn item(lexer: &::logos::Lexer<T, S>, token: T, span: Span) -> Self::Item;
+}
+
+/// A [`Mode`](./trait.Mode.html) that yields just the token
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Tokens;
+
+/// A [`Mode`](./trait.Mode.html) that yields a token wrapped with a [`Span`](./struct.Span.html)
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Spanned;
+
+/// A [`Mode`](./trait.Mode.html) that yields a token wrapped with a
+/// [`Span`](./struct.Span.html) along with its slice of the source
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Sliced;
+
+impl<T, S> Mode<T, S> for Tokens
+where
+    T: Clone + ::logos::Logos,
+{
+    type Item = T;
+    const USES_SPAN: bool = false;
+    fn item(_: &::logos::Lexer<T, S>, token: T, _: Span) -> Self::Item {
+        token
+    }
+}
+
+impl<T, S> Mode<T, S> for Spanned
+where
+    T: Clone + ::logos::Logos,
+{
+    type Item = WithSpan<T>;
+    fn item(_: &::logos::Lexer<T, S>, token: T, span: Span) -> Self::Item {
+        WithSpan::new(token, span)
+    }
+}
+
+impl<'a, T> Mode<T, &'a str> for Sliced
+where
+    T: Clone + ::logos::Logos + ::logos::source::WithSource<&'a str>,
+{
+    type Item = (WithSpan<T>, &'a str);
+    fn item(lexer: &::logos::Lexer<T, &'a str>, token: T, span: Span) -> Self::Item {
+        let source: &'a str = lexer.source;
+        (WithSpan::new(token, span), &source[span])
+    }
+}
+
+impl<'a, T> Mode<T, &'a [u8]> for Sliced
+where
+    T: Clone + ::logos::Logos + ::logos::source::WithSource<&'a [u8]>,
+{
+    type Item = (WithSpan<T>, &'a [u8]);
+    fn item(lexer: &::logos::Lexer<T, &'a [u8]>, token: T, span: Span) -> Self::Item {
+        let source: &'a [u8] = lexer.source;
+        (WithSpan::new(token, span), &source[span])
+    }
+}
+
+/// An extension trait for creating lexers directly from the token type
+///
+/// This is implemented for every
+/// [`#[derive(Logos)]`](https://docs.rs/logos/latest/logos/trait.Logos.html) type
+///
+/// ```rust
+/// # use logos::Logos;
+/// use logos_iterator::LogosIteratorExt as _;
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[0-9]"]
+///     Digit,
+///     #[token = "+"]
+///     Plus,
+/// }
+///
+/// let tokens = Token::tokens("1 + 2").collect::<Vec<_>>();
+/// assert_eq!(tokens, vec![Token::Digit, Token::Plus, Token::Digit]);
+///
+/// let spans = Token::spanned_tokens("1 + 2").map(|k| k.span.start).collect::<Vec<_>>();
+/// assert_eq!(spans, vec![0, 2, 4]);
+/// ```
+pub trait LogosIteratorExt: PartialEq<Self> + ::logos::Logos {
+    /// Create a [`Lexer`](./struct.Lexer.html) over `s` that yields just the tokens
+    fn tokens<'a, S>(s: S) -> Lexer<Self, S>
+    where
+        Self: ::logos::source::WithSource<S>,
+        S: ::logos::source::Source<'a>,
+    {
+        Lexer::new(s)
+    }
+
+    /// Create a [`SpannedLexer`](./type.SpannedLexer.html) over `s` that yields
+    /// the tokens wrapped with their [`Span`](./struct.Span.html)
+    fn spanned_tokens<'a, S>(s: S) -> SpannedLexer<Self, S>
+    where
+        Self: ::logos::source::WithSource<S>,
+        S: ::logos::source::Source<'a>,
+    {
+        Lexer::new(s)
+    }
+}
+
+impl<T> LogosIteratorExt for T where T: PartialEq<T> + ::logos::Logos {}
+
+#[cfg(test)]
+mod tests {
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+    }
+
+    // tokens only need to be `Clone`
+    #[derive(Logos, PartialEq, Clone, Debug)]
+    enum Owned {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+    }
+
+    #[test]
+    fn non_copy() {
+        let input = "ab $$ cd";
+        let tokens = crate::lex::<Owned, _>(input)
+            .merge_errors()
+            .with_slices()
+            .map(|(k, slice)| (k.item, slice))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec![
+                (Owned::Word, "ab"),
+                (Owned::Unknown, "$$"),
+                (Owned::Word, "cd")
+            ]
+        );
+        assert_eq!(crate::lex_to_vec::<Owned, _>(input).len(), 4);
+    }
+
+    #[test]
+    fn merge_errors() {
+        let input = "ab $$$ cd $ $";
+        let errors = crate::lex::<Token, _>(input)
+            .with_slices()
+            .filter(|(k, _)| k.item == Token::Unknown)
+            .count();
+        assert_eq!(errors, 5);
+
+        let errors = crate::lex::<Token, _>(input)
+            .merge_errors()
+            .with_slices()
+            .filter(|(k, _)| k.item == Token::Unknown)
+            .map(|(k, slice)| (k.span.start, k.span.end, slice))
+            .collect::<Vec<_>>();
+        assert_eq!(errors, vec![(3, 6, "$$$"), (10, 11, "$"), (12, 13, "$")]);
+
+        let tokens = crate::lex::<Token, _>(input)
+            .merge_errors()
+            .collect::<Vec<_>>();
+        assert_eq!(tokens.len(), 5);
+    }
+
+    #[test]
+    fn lex_to_vec() {
+        let input = "ab $ cd ef";
+        let tokens = crate::lex_to_vec::<Token, _>(input);
+        assert_eq!(
+            tokens,
+            crate::lex::<Token, _>(input).spanned().collect::<Vec<_>>()
+        );
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(crate::lex_to_vec_with_hint::<Token, _>(input, 0).len(), 4);
+        assert!(crate::lex_to_vec::<Token, _>("").is_empty());
+    }
+
+    #[test]
+    fn next_batch() {
+        let mut lexer = crate::lex::<Token, _>("a b c d e").spanned();
+        let mut buf = vec![];
+        assert_eq!(lexer.next_batch(&mut buf, 2), 2);
+        assert_eq!(lexer.next_batch(&mut buf, 2), 2);
+        assert_eq!(lexer.next_batch(&mut buf, 2), 1);
+        assert_eq!(lexer.next_batch(&mut buf, 2), 0);
+        assert_eq!(buf.len(), 5);
+        assert_eq!(buf[4].span, crate::Span::from(8..9));
+
+        let mut lexer = crate::lex::<Token, _>("a b c").spanned();
+        let mut buf = vec![];
+        assert_eq!(lexer.next_batch(&mut buf, usize::MAX), 3);
+        assert!(buf.capacity() < 64);
+        assert_eq!(lexer.next_batch(&mut buf, usize::MAX), 0);
+    }
+
+    #[test]
+    fn fold() {
+        let input = "ab $$ cd";
+        let spans =
+            crate::lex::<Token, _>(input)
+                .merge_errors()
+                .spanned()
+                .fold(vec![], |mut spans, k| {
+                    spans.push(k.span);
+                    spans
+                });
+        assert_eq!(
+            spans,
+            crate::lex::<Token, _>(input)
+                .merge_errors()
+                .spanned()
+                .map(|k| k.span)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(spans.len(), 3);
+    }
+}
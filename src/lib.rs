@@ -2,7 +2,7 @@
 //!
 //! ## Example
 //! ```rust
-//! # use logos_iterator::{Lexer, SpannedLexer};
+//! # use logos_iterator::{lex, Lexer, SpannedLexer};
 //! # use logos::Logos;
 //! // see the documentation for Logos on how this derive works
 //! #[derive(Logos, PartialEq, Clone, Copy, Debug)]
@@ -53,151 +53,183 @@
 //! // or without spans
 //! let tokens = Lexer::<Token, _>::new(input).collect::<Vec<_>>();
 //! assert_eq!(tokens, expected);
+//!
+//! // or with `lex`, choosing what is yielded with an adapter
+//! let tokens = lex::<Token, _>(input).collect::<Vec<_>>();
+//! assert_eq!(tokens, expected);
+//!
+//! let (token, slice) = lex::<Token, _>(input).with_slices().nth(2).unwrap();
+//! assert_eq!((token.item, slice), (Token::Digit, "1"));
+//! assert_eq!(&input[token.span], "1");
 //! ```
+//!
+//! ## Features
+//! * `std` (enabled by default): the `io` writers, `Instrumented`, `testing::corpus` and the
+//!   `std::error::Error` impls. Without it, this crate is `no_std` and only needs `alloc`
+//! * `lending`: [`LendingLexer`](./struct.LendingLexer.html), which lends tokens
+//!   borrowed from the lexer so they needn't be `Clone`
+//! * `ffi`: the [`ffi`](./ffi/index.html) module and `export_c_lexer!`, for C callers
 
-/// A lexer that is an iterator over an input source, `S` that yields token `T` until
-/// the
-/// [`#[logos::end]`](https://docs.rs/logos/latest/logos/trait.Logos.html#associatedconstant.END)
-/// token is found
-///
-/// The yielded element is wrapped with a [`Span`](./struct.Span.html), which is
-/// the byte offset into the source that the token is located at
-pub struct SpannedLexer<T, S>(::logos::Lexer<T, S>)
-where
-    T: PartialEq<T> + ::logos::Logos;
-
-impl<'a, T, S> SpannedLexer<T, S>
-where
-    T: PartialEq<T> + ::logos::Logos + ::logos::source::WithSource<S>,
-    S: ::logos::source::Source<'a>,
-{
-    /// Create a new lexer from the source `S`
-    ///
-    /// * T is the Token (that
-    ///   [`#[derive(Logos)]`](https://docs.rs/logos/latest/logos/trait.Logos.html)
-    ///   applies to)
-    /// * S is something that implements
-    ///   [`logos::Source`](https://docs.rs/logos/latest/logos/source/trait.Source.html)
-    ///   (`&str`, `&[u8]`, etc)
-    pub fn new(s: S) -> Self {
-        Self(T::lexer(s))
-    }
-}
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-impl<'a, T, S> Iterator for SpannedLexer<T, S>
-where
-    T: Copy + Clone + PartialEq<T>,
-    T: ::logos::Logos + ::logos::source::WithSource<S>,
-    S: ::logos::source::Source<'a>,
-{
-    type Item = WithSpan<T>;
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.0.token == T::END {
-            return None;
-        }
-
-        let token = self.0.token;
-        let range = self.0.range();
-        let span = Span {
-            start: range.start,
-            end: range.end,
-        };
-
-        self.0.advance();
-        Some(WithSpan::new(token, span))
-    }
-}
+#[cfg_attr(not(any(feature = "std", test)), macro_use)]
+extern crate alloc;
 
-/// A lexer that is an iterator over an input source, `S` that yields token `T` until
-/// the
-/// [`#[logos::end]`](https://docs.rs/logos/latest/logos/trait.Logos.html#associatedconstant.END)
-/// token is found
-pub struct Lexer<T, S>(::logos::Lexer<T, S>)
-where
-    T: PartialEq<T> + ::logos::Logos;
-
-impl<'a, T, S> Lexer<T, S>
-where
-    T: PartialEq<T> + ::logos::Logos + ::logos::source::WithSource<S>,
-    S: ::logos::source::Source<'a>,
-{
-    /// Create a new lexer from the source `S`
-    ///
-    /// * T is the Token (that
-    ///   [`#[derive(Logos)]`](https://docs.rs/logos/latest/logos/trait.Logos.html)
-    ///   applies to)
-    /// * S is something that implements
-    ///   [`logos::Source`](https://docs.rs/logos/latest/logos/source/trait.Source.html)
-    ///   (`&str`, `&[u8]`, etc)
-    pub fn new(s: S) -> Self {
-        Self(T::lexer(s))
-    }
-}
+// so the derives can refer to `::logos_iterator` in this crate
+extern crate self as logos_iterator;
 
-impl<'a, T, S> Iterator for Lexer<T, S>
-where
-    T: Copy + Clone + PartialEq<T>,
-    T: ::logos::Logos + ::logos::source::WithSource<S>,
-    S: ::logos::source::Source<'a>,
-{
-    type Item = T;
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.0.token == T::END {
-            return None;
-        }
-
-        let token = self.0.token;
-        self.0.advance();
-        Some(token)
-    }
-}
+mod lexer;
+pub use lexer::{
+    lex, lex_to_vec, lex_to_vec_with_hint, Lexer, LogosIteratorExt, Mode, Sliced, Spanned,
+    SpannedLexer, Tokens, MAX_RESERVED_TOKENS,
+};
 
-/// `WithSpan` wraps something with a [`Span`](./struct.Span.html)
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub struct WithSpan<T> {
-    pub item: T,
-    pub span: Span,
-}
+mod span;
+pub use span::{
+    slice_bytes, slice_str, GetSpan, SliceError, Span, SpanDisplay, SpanResolver, WithSpan,
+};
 
-impl<T> WithSpan<T> {
-    /// Wrap `item` with [`span`](./struct.Span.html)
-    pub fn new(item: T, span: Span) -> Self {
-        Self { item, span }
-    }
-}
+mod position;
+pub use position::{ColumnMode, LineCol, LineIndex, LineTracker, Located, PositionedLexer};
 
-/// `Span` represents a `start`..`end` range
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub struct Span {
-    pub start: usize,
-    pub end: usize,
-}
+mod chars;
+pub use chars::{CharIndex, CharSpannedLexer};
 
-impl std::ops::Index<Span> for str {
-    type Output = str;
-    fn index(&self, index: Span) -> &Self::Output {
-        self.index(index.start..index.end)
-    }
-}
+mod decode;
+pub use decode::{DecodedLexer, DecodedSource, Encoding};
 
-impl std::ops::Index<Span> for String {
-    type Output = str;
-    fn index(&self, index: Span) -> &Self::Output {
-        self.index(index.start..index.end)
-    }
-}
+mod stream;
+pub use stream::{TokenContext, TokenId, TokenStream};
+
+mod interval;
+pub use interval::IntervalIndex;
+
+mod excerpt;
+pub use excerpt::{excerpt, lines_with_spans, Excerpt, ExcerptLine, LinesWithSpans};
+
+mod iter;
+pub use iter::{CheckedSlicesIn, Coalesce, SlicesIn, TokenIteratorExt, WithDepth, WithSkipped};
+
+mod filter;
+pub use filter::{
+    CoalesceFilter, LexPipeline, MapFilter, PassStats, Pipeline, PipelineIter, RetainFilter,
+    TokenFilter,
+};
+
+mod expand;
+pub use expand::{ExpandError, Expanded, Expander};
+
+mod conditional;
+pub use conditional::{Conditionals, Region};
+
+mod set;
+pub use set::TokenSet;
+
+mod cursor;
+pub use cursor::Cursor;
+
+mod brackets;
+pub use brackets::{check_brackets, BracketError};
+
+mod indent;
+pub use indent::{check_indentation, IndentWarning};
+
+mod origin;
+pub use origin::{Origin, TokenMeta};
+
+mod tree;
+pub use tree::{Group, NodeId, TokenTree, TreeIndex};
+
+mod visit;
+pub use visit::{walk_group, walk_stream, walk_tree, walk_trees, TokenTreeVisitor, TokenVisitor};
+
+mod export;
+pub use export::to_json;
+#[cfg(feature = "std")]
+pub use export::{write_csv, write_json_lines, write_tsv};
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn span_index() {
-        let s = "this is a test";
-        let span = Span { start: 5, end: 9 };
-        assert_eq!("is a", &s[span]);
-
-        let s = String::from(s);
-        let span = Span { start: 5, end: 9 };
-        assert_eq!("is a", &s[span]);
-    }
+mod dump;
+pub use dump::{to_dot, to_sexp, TokenTable};
+
+#[cfg(feature = "std")]
+mod instrument;
+#[cfg(feature = "std")]
+pub use instrument::Instrumented;
+
+mod name;
+pub use name::TokenName;
+
+mod macros;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use ::logos::Logos;
+    pub use alloc::string::{String, ToString};
 }
+
+mod expected;
+pub use expected::{Expected, ExpectedMessage};
+
+mod precedence;
+pub use precedence::{Assoc, Fixity, Operator, PrecedenceTable};
+
+mod fold;
+pub use fold::{fold_expr, FoldError};
+
+mod docs;
+pub use docs::{extract_docs, DocBlock};
+
+mod stats;
+pub use stats::{KindStats, RecordStats, TokenStats};
+
+mod trivia;
+pub use trivia::{AttachPolicy, Attached, TriviaAttacher, TriviaStream};
+
+mod edit;
+pub use edit::{EditError, SpanMapper, TextEdit, TextEdits};
+
+mod rewrite;
+pub use rewrite::{rename, rewrite, strip_comments, Rewritten};
+
+mod source_map;
+pub use source_map::{CompositeSource, FileId, FileSpan, Segment, SourceFile, SourceMap};
+
+mod raw;
+pub use raw::ResumableLexer;
+
+#[cfg(feature = "lending")]
+mod lending;
+#[cfg(feature = "lending")]
+pub use lending::{LendingIterator, LendingLexer};
+
+mod input;
+pub use input::TokenInput;
+
+mod hover;
+pub use hover::{hover_at, hover_at_with, Hover};
+
+mod diagnostic;
+pub use diagnostic::{
+    apply_fixes, Applicability, Diagnostic, DiagnosticDisplay, Severity, Suggestion,
+};
+
+mod sarif;
+pub use sarif::Sarif;
+
+mod has_span;
+pub use has_span::HasSpan;
+
+mod validate;
+pub use validate::{validate, SpanProblem, SpanReport, SpanValidator};
+
+mod islands;
+pub use islands::{islands, Island, Islands, TemplateLexer, TemplateToken};
+
+mod width;
+
+pub mod combinator;
+
+pub mod testing;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
@@ -0,0 +1,340 @@
+/// Generate a `wasm_bindgen` function that lexes a string into a JSON token dump
+///
+/// The function takes the source as a `&str` and returns the JSON produced by
+/// [`to_json`](./fn.to_json.html). The crate using this needs to depend on
+/// `wasm-bindgen`
+///
+/// ```rust,ignore
+/// # use logos::Logos;
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Word,
+/// }
+///
+/// // generates `pub fn tokenize(source: &str) -> String`, exported to JS
+/// logos_iterator::export_wasm_lexer!(tokenize, Token);
+/// ```
+#[macro_export]
+macro_rules! export_wasm_lexer {
+    ($name:ident, $token:ty) => {
+        $crate::export_wasm_lexer!(@with #[::wasm_bindgen::prelude::wasm_bindgen] $name, $token);
+    };
+    // the attribute is passed in so the tests can expand this without `wasm-bindgen`
+    (@with #[$attr:meta] $name:ident, $token:ty) => {
+        #[$attr]
+        pub fn $name(source: &str) -> ::std::string::String {
+            let tokens = $crate::lex::<$token, &str>(source)
+                .spanned()
+                .collect::<$crate::TokenStream<_>>();
+            $crate::to_json(&tokens, source)
+        }
+    };
+}
+
+/// Declare a lexer type for a token type, with inherent methods for each way of lexing
+///
+/// This is useful for exporting a non-generic lexer from a library. The type
+/// borrows a `&str` source and has these methods:
+/// * `new(source)` creates the lexer
+/// * `source()` gets the source
+/// * `tokens()` lexes the tokens, as a [`Lexer`](./struct.Lexer.html)
+/// * `spanned()` lexes the spanned tokens, as a [`SpannedLexer`](./type.SpannedLexer.html)
+/// * `with_slices()` lexes the spanned tokens along with their slices
+/// * `dump()` renders the tokens as a [`TokenTable`](./struct.TokenTable.html)
+///
+/// ```rust
+/// # use logos::Logos;
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// pub enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Word,
+/// }
+///
+/// logos_iterator::declare_lexer! {
+///     /// A lexer for words
+///     pub struct WordLexer(Token);
+/// }
+///
+/// let lexer = WordLexer::new("hello world");
+/// assert_eq!(lexer.tokens().count(), 2);
+/// assert_eq!(lexer.with_slices().last().map(|(_, s)| s), Some("world"));
+/// assert!(lexer.dump().contains("Word"));
+/// ```
+#[macro_export]
+macro_rules! declare_lexer {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($token:ty);) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug)]
+        $vis struct $name<'a> {
+            source: &'a str,
+        }
+
+        impl<'a> $name<'a> {
+            /// Create a lexer over the `source`
+            pub fn new(source: &'a str) -> Self {
+                Self { source }
+            }
+
+            /// The source that is lexed
+            pub fn source(&self) -> &'a str {
+                self.source
+            }
+
+            /// Lex the tokens
+            pub fn tokens(&self) -> $crate::Lexer<$token, &'a str> {
+                $crate::lex(self.source)
+            }
+
+            /// Lex the tokens, wrapped with their span
+            pub fn spanned(&self) -> $crate::SpannedLexer<$token, &'a str> {
+                $crate::lex(self.source).spanned()
+            }
+
+            /// Lex the spanned tokens, along with their slice of the source
+            pub fn with_slices(&self) -> $crate::Lexer<$token, &'a str, $crate::Sliced> {
+                $crate::lex(self.source).with_slices()
+            }
+
+            /// Render the tokens as a table
+            pub fn dump(&self) -> $crate::__private::String {
+                let tokens = $crate::lex_to_vec::<$token, &'a str>(self.source);
+                $crate::__private::ToString::to_string(&$crate::TokenTable::new(&tokens, self.source))
+            }
+        }
+    };
+}
+
+/// Declare an enum that is the union of token types, for matching on a stream
+/// that mixes them
+///
+/// Each variant holds one of the token types, which must be different. This
+/// generates:
+/// * `From` for each of the token types
+/// * `is_error()` and `is_end()`, which check the token against its
+///   [`Logos::ERROR`](https://docs.rs/logos/latest/logos/trait.Logos.html#associatedconstant.ERROR)
+///   and [`Logos::END`](https://docs.rs/logos/latest/logos/trait.Logos.html#associatedconstant.END)
+/// * `From<Island<A, B>>` when there are two variants, which turns the outer
+///   tokens of an [`Islands`](./struct.Islands.html) lexer into the first one and
+///   the inner tokens into the second
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{islands, Span, WithSpan};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// pub enum Text {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Word,
+///     #[token = "$("]
+///     Open,
+/// }
+///
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// pub enum Shell {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Command,
+///     #[token = ")"]
+///     Close,
+/// }
+///
+/// logos_iterator::token_union! {
+///     /// A token of either language
+///     #[derive(PartialEq, Clone, Copy, Debug)]
+///     pub enum Token {
+///         Text(Text),
+///         Shell(Shell),
+///     }
+/// }
+///
+/// let tokens = islands("hi $(whoami) ?", Text::Open, Shell::Close)
+///     .map(|k| k.map(Token::from))
+///     .collect::<Vec<_>>();
+/// assert_eq!(tokens[2], WithSpan::new(Token::Shell(Shell::Command), Span::from(5..11)));
+/// assert!(tokens[4].item.is_error());
+/// assert_eq!(Token::from(Text::Word), Token::Text(Text::Word));
+/// ```
+#[macro_export]
+macro_rules! token_union {
+    (@enum $(#[$meta:meta])* $vis:vis enum $name:ident {
+        $($(#[$variant_meta:meta])* $variant:ident($ty:ty)),+
+    }) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant($ty)),+
+        }
+
+        $(
+            impl ::core::convert::From<$ty> for $name {
+                fn from(token: $ty) -> Self {
+                    Self::$variant(token)
+                }
+            }
+        )+
+
+        impl $name {
+            /// Whether this is the error token of its token type
+            pub fn is_error(&self) -> bool {
+                match self {
+                    $(Self::$variant(token) => *token == <$ty as $crate::__private::Logos>::ERROR,)+
+                }
+            }
+
+            /// Whether this is the end token of its token type
+            pub fn is_end(&self) -> bool {
+                match self {
+                    $(Self::$variant(token) => *token == <$ty as $crate::__private::Logos>::END,)+
+                }
+            }
+        }
+    };
+
+    ($(#[$meta:meta])* $vis:vis enum $name:ident {
+        $(#[$first_meta:meta])* $first:ident($outer:ty),
+        $(#[$second_meta:meta])* $second:ident($inner:ty) $(,)?
+    }) => {
+        $crate::token_union! {
+            @enum $(#[$meta])* $vis enum $name {
+                $(#[$first_meta])* $first($outer),
+                $(#[$second_meta])* $second($inner)
+            }
+        }
+
+        impl ::core::convert::From<$crate::Island<$outer, $inner>> for $name {
+            fn from(token: $crate::Island<$outer, $inner>) -> Self {
+                match token {
+                    $crate::Island::Outer(token) => Self::$first(token),
+                    $crate::Island::Inner(token) => Self::$second(token),
+                }
+            }
+        }
+    };
+
+    ($(#[$meta:meta])* $vis:vis enum $name:ident {
+        $($(#[$variant_meta:meta])* $variant:ident($ty:ty)),+ $(,)?
+    }) => {
+        $crate::token_union! {
+            @enum $(#[$meta])* $vis enum $name {
+                $($(#[$variant_meta])* $variant($ty)),+
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[0-9]+"]
+        Number,
+    }
+
+    crate::declare_lexer! {
+        struct NumberLexer(Token);
+    }
+
+    // `#[inline]` stands in for `#[wasm_bindgen]`, so the generated glue is checked
+    crate::export_wasm_lexer!(@with #[inline] tokenize, Token);
+
+    #[test]
+    fn export_wasm_lexer() {
+        let source = "1 23";
+        let tokens = crate::lex::<Token, _>(source)
+            .spanned()
+            .collect::<crate::TokenStream<_>>();
+        assert_eq!(tokenize(source), crate::to_json(&tokens, source));
+        assert!(tokenize(source).contains("Number"));
+    }
+
+    #[test]
+    fn declare_lexer() {
+        let lexer = NumberLexer::new("1 23");
+        assert_eq!(lexer.source(), "1 23");
+        assert_eq!(
+            lexer.tokens().collect::<Vec<_>>(),
+            vec![Token::Number, Token::Number]
+        );
+        assert_eq!(
+            lexer.spanned().map(|k| k.span).last(),
+            Some(crate::Span::from(2..4))
+        );
+        assert_eq!(lexer.with_slices().map(|(_, s)| s).nth(1), Some("23"));
+        assert_eq!(
+            lexer.dump(),
+            "# | kind   | span | pos | text\n\
+             0 | Number | 0..1 | 1:1 | \"1\"\n\
+             1 | Number | 2..4 | 1:3 | \"23\"\n"
+        );
+    }
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Letters {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+    }
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Punct {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[token = ","]
+        Comma,
+    }
+
+    crate::token_union! {
+        #[derive(PartialEq, Debug)]
+        enum Any {
+            Number(Token),
+            /// Words
+            Word(Letters),
+            Punct(Punct),
+        }
+    }
+
+    #[test]
+    fn token_union() {
+        assert_eq!(Any::from(Letters::Word), Any::Word(Letters::Word));
+        assert_eq!(Any::from(Punct::Comma), Any::Punct(Punct::Comma));
+
+        let classes = [
+            Any::from(Token::Number),
+            Any::from(Token::Unknown),
+            Any::from(Letters::Eof),
+            Any::from(Punct::Unknown),
+        ]
+        .iter()
+        .map(|k| (k.is_error(), k.is_end()))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            classes,
+            vec![(false, false), (true, false), (false, true), (true, false)]
+        );
+    }
+}
@@ -0,0 +1,62 @@
+/// `TokenName` is a human-readable name for a token kind, for use in messages
+///
+/// This can be derived. Each variant is named with `#[name("...")]`, or its own
+/// name by default
+///
+/// ```rust
+/// # use logos::Logos;
+/// use logos_iterator::TokenName;
+///
+/// #[derive(Logos, TokenName, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     #[name("end of input")]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     #[name("identifier")]
+///     Ident,
+///     #[token = ";"]
+///     #[name("`;`")]
+///     Semi,
+/// }
+///
+/// assert_eq!(Token::Semi.token_name(), "`;`");
+/// assert_eq!(Token::Unknown.token_name(), "Unknown");
+/// ```
+pub trait TokenName {
+    /// The name of this kind of token
+    fn token_name(&self) -> &'static str;
+}
+
+pub use logos_iterator_derive::TokenName;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, TokenName, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        #[name("end of input")]
+        Eof,
+        #[error]
+        Unknown,
+        #[token = ";"]
+        #[name("';'")]
+        Semi,
+        #[regex = "[a-z]+"]
+        Ident,
+    }
+
+    #[test]
+    fn derive() {
+        let names = crate::lex::<Token, _>("a;?")
+            .chain(Some(Token::Eof))
+            .map(|k| k.token_name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Ident", "';'", "Unknown", "end of input"]);
+    }
+}
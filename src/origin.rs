@@ -0,0 +1,139 @@
+use crate::{Expanded, Span, WithSpan};
+
+/// `Origin` is where a token came from
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Origin {
+    /// The token is in the source, at this span
+    Source(Span),
+    /// The token was inserted by a pass, at the `anchor` span
+    Synthetic {
+        inserted_by: &'static str,
+        anchor: Span,
+    },
+    /// The token came from an expansion at the `call_site`, and was defined at the `def_site`
+    Expanded { call_site: Span, def_site: Span },
+}
+
+impl Origin {
+    /// The span that diagnostics should point at
+    ///
+    /// This is the source span, the anchor of a synthetic token, or the call
+    /// site of an expanded token
+    pub fn span(&self) -> Span {
+        match *self {
+            Self::Source(span) => span,
+            Self::Synthetic { anchor, .. } => anchor,
+            Self::Expanded { call_site, .. } => call_site,
+        }
+    }
+
+    /// Whether the token is in the source
+    pub fn is_source(&self) -> bool {
+        matches!(self, Self::Source(..))
+    }
+
+    /// Whether the token was inserted by a pass
+    pub fn is_synthetic(&self) -> bool {
+        matches!(self, Self::Synthetic { .. })
+    }
+
+    /// Whether the token came from an expansion
+    pub fn is_expanded(&self) -> bool {
+        matches!(self, Self::Expanded { .. })
+    }
+}
+
+/// `TokenMeta` is a token along with its [`Origin`](./enum.Origin.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TokenMeta<T> {
+    pub item: T,
+    pub origin: Origin,
+}
+
+impl<T> TokenMeta<T> {
+    /// Create a token that is in the source at `span`
+    pub fn source(item: T, span: Span) -> Self {
+        Self {
+            item,
+            origin: Origin::Source(span),
+        }
+    }
+
+    /// Create a token that was inserted by the pass `inserted_by`, at the `anchor` span
+    pub fn synthetic(item: T, inserted_by: &'static str, anchor: Span) -> Self {
+        Self {
+            item,
+            origin: Origin::Synthetic {
+                inserted_by,
+                anchor,
+            },
+        }
+    }
+
+    /// The span that diagnostics should point at
+    pub fn span(&self) -> Span {
+        self.origin.span()
+    }
+
+    /// Convert this into a [`WithSpan`](./struct.WithSpan.html), using [`span`](#method.span)
+    pub fn into_spanned(self) -> WithSpan<T> {
+        let span = self.span();
+        WithSpan::new(self.item, span)
+    }
+}
+
+impl<T> From<WithSpan<T>> for TokenMeta<T> {
+    fn from(token: WithSpan<T>) -> Self {
+        Self::source(token.item, token.span)
+    }
+}
+
+impl<T> From<Expanded<T>> for TokenMeta<T> {
+    fn from(token: Expanded<T>) -> Self {
+        let origin = match token.def_site {
+            Some(def_site) => Origin::Expanded {
+                call_site: token.span,
+                def_site,
+            },
+            None => Origin::Source(token.span),
+        };
+        Self {
+            item: token.item,
+            origin,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origin() {
+        let source = TokenMeta::from(WithSpan::new('a', Span::from(0..1)));
+        assert!(source.origin.is_source());
+        assert_eq!(source.span(), Span::from(0..1));
+
+        let synthetic = TokenMeta::synthetic(';', "asi", Span::from(1..1));
+        assert!(synthetic.origin.is_synthetic());
+        assert_eq!(
+            synthetic.into_spanned(),
+            WithSpan::new(';', Span::from(1..1))
+        );
+
+        let expanded = TokenMeta::from(Expanded {
+            item: 'b',
+            span: Span::from(5..8),
+            def_site: Some(Span::from(2..3)),
+            call_sites: vec![Span::from(5..8)],
+        });
+        assert_eq!(
+            expanded.origin,
+            Origin::Expanded {
+                call_site: Span::from(5..8),
+                def_site: Span::from(2..3)
+            }
+        );
+        assert_eq!(expanded.span(), Span::from(5..8));
+    }
+}
@@ -0,0 +1,465 @@
+use crate::width::{self, Width};
+use crate::{Lexer, Span, Spanned, WithSpan};
+use alloc::vec::Vec;
+
+/// `LineCol` is a 1-based `line`:`column` position in a source
+///
+/// Columns are counted by the [`ColumnMode`](./enum.ColumnMode.html) (bytes, by
+/// default), with a `\t` moving to the next tab stop (see
+/// [`LineIndex::with_tab_width`](./struct.LineIndex.html#method.with_tab_width))
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LineCol {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Default for LineCol {
+    fn default() -> Self {
+        Self { line: 1, column: 1 }
+    }
+}
+
+/// `ColumnMode` decides how the columns of a [`LineCol`](./struct.LineCol.html) are counted
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum ColumnMode {
+    /// Each byte is a column
+    #[default]
+    Bytes,
+    /// Each `char` is a column
+    Chars,
+    /// Columns are the display width in a terminal
+    ///
+    /// East Asian wide chars and emoji are 2 columns, while combining marks
+    /// and chars joined with a zero width joiner are part of the previous char.
+    /// This is an approximation of grapheme clusters, using a built-in table
+    Width,
+}
+
+/// `Located` wraps something with a [`Span`](./struct.Span.html) and the
+/// [`LineCol`](./struct.LineCol.html) that the span `start`s and `end`s at
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Located<T> {
+    pub item: T,
+    pub span: Span,
+    pub start: LineCol,
+    pub end: LineCol,
+}
+
+impl<T> Located<T> {
+    /// Remove the line and column information
+    pub fn into_spanned(self) -> WithSpan<T> {
+        WithSpan::new(self.item, self.span)
+    }
+}
+
+/// `LineTracker` incrementally tracks the [`LineCol`](./struct.LineCol.html)
+/// while moving forward through a source
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LineTracker {
+    offset: usize,
+    pos: LineCol,
+    tab_width: usize,
+    columns: ColumnMode,
+    width: Width,
+}
+
+impl Default for LineTracker {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            pos: LineCol::default(),
+            tab_width: 1,
+            columns: ColumnMode::default(),
+            width: Width::default(),
+        }
+    }
+}
+
+impl LineTracker {
+    /// Create a new tracker at the start of a source
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use tab stops every `width` columns when a `\t` is found
+    ///
+    /// The default of `1` counts a `\t` as a single column
+    pub fn with_tab_width(mut self, width: usize) -> Self {
+        self.tab_width = width.max(1);
+        self
+    }
+
+    /// Count columns with the [`ColumnMode`](./enum.ColumnMode.html)
+    pub fn with_columns(mut self, columns: ColumnMode) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// The byte offset the tracker is at
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The position the tracker is at
+    pub fn position(&self) -> LineCol {
+        self.pos
+    }
+
+    /// Move the tracker over `bytes`, which must directly follow the current offset
+    pub fn advance(&mut self, bytes: &[u8]) -> LineCol {
+        if self.columns == ColumnMode::Bytes {
+            for &byte in bytes {
+                self.advance_char(byte as char)
+            }
+        } else {
+            width::for_each_char(bytes, |_, _, c| self.advance_char(c.unwrap_or('\0')));
+        }
+        self.offset += bytes.len();
+        self.pos
+    }
+
+    fn advance_char(&mut self, c: char) {
+        // every char goes through the width, so that a `\n` or `\t` ends a ZWJ sequence
+        let width = match self.columns {
+            ColumnMode::Width => self.width.of(c),
+            _ => 1,
+        };
+        match c {
+            '\n' => {
+                self.pos.line += 1;
+                self.pos.column = 1;
+            }
+            '\t' => self.pos.column = next_tab_stop(self.pos.column, self.tab_width),
+            _ => self.pos.column += width,
+        }
+    }
+
+    /// Move the tracker forward in `source` to `offset`
+    ///
+    /// If `offset` is behind the tracker, the current position is returned
+    pub fn advance_to<'a, S>(&mut self, source: &S, offset: usize) -> LineCol
+    where
+        S: ::logos::source::Source<'a>,
+    {
+        if offset <= self.offset {
+            return self.pos;
+        }
+        match source.slice(self.offset..offset) {
+            Some(slice) => self.advance(::logos::source::Slice::as_bytes(&slice)),
+            None => self.pos,
+        }
+    }
+}
+
+/// `LineIndex` maps byte offsets in a source to [`LineCol`](./struct.LineCol.html) positions
+///
+/// This only stores where each line starts (and where the tabs and non-ASCII
+/// chars are), so the source doesn't have to be retained
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineIndex {
+    starts: Vec<usize>,
+    special: Vec<Special>,
+    tab_width: usize,
+    columns: ColumnMode,
+    len: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Special {
+    offset: usize,
+    kind: SpecialKind,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum SpecialKind {
+    Tab,
+    Char { len: u8, width: u8 },
+}
+
+impl LineIndex {
+    /// Create a new index over `source`
+    pub fn new(source: impl AsRef<[u8]>) -> Self {
+        let source = source.as_ref();
+        let (mut starts, mut special) = (vec![0], vec![]);
+        let mut width = Width::default();
+        width::for_each_char(source, |offset, len, c| {
+            // like a `LineTracker`, every char goes through the width, and an
+            // ASCII char is special if it has been joined by a ZWJ
+            let c = c.unwrap_or('\0');
+            let char_width = width.of(c);
+            let kind = match c {
+                '\n' => {
+                    starts.push(offset + 1);
+                    return;
+                }
+                '\t' => SpecialKind::Tab,
+                c if !c.is_ascii() || char_width != 1 => SpecialKind::Char {
+                    len: len as u8,
+                    width: char_width as u8,
+                },
+                _ => return,
+            };
+            special.push(Special { offset, kind })
+        });
+        Self {
+            starts,
+            special,
+            tab_width: 1,
+            columns: ColumnMode::default(),
+            len: source.len(),
+        }
+    }
+
+    /// Use tab stops every `width` columns when a `\t` is found
+    ///
+    /// The default of `1` counts a `\t` as a single column
+    pub fn with_tab_width(mut self, width: usize) -> Self {
+        self.tab_width = width.max(1);
+        self
+    }
+
+    /// Count columns with the [`ColumnMode`](./enum.ColumnMode.html)
+    pub fn with_columns(mut self, columns: ColumnMode) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// The number of lines in the source
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// The byte offset that the 1-based `line` starts at
+    pub fn line_start(&self, line: usize) -> Option<usize> {
+        line.checked_sub(1)
+            .and_then(|line| self.starts.get(line).copied())
+    }
+
+    /// Get the [`LineCol`](./struct.LineCol.html) for the byte `offset`
+    ///
+    /// Offsets past the end of the source are clamped to the end
+    pub fn line_col(&self, offset: usize) -> LineCol {
+        let offset = offset.min(self.len);
+        let line = match self.starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+
+        let (mut pos, mut column) = (self.starts[line], 1);
+        let first = self.special.partition_point(|s| s.offset < pos);
+        let special = self.special[first..]
+            .iter()
+            .take_while(|s| s.offset < offset)
+            .filter(|s| s.kind == SpecialKind::Tab || self.columns != ColumnMode::Bytes);
+
+        for s in special {
+            column += s.offset - pos;
+            match s.kind {
+                SpecialKind::Tab => {
+                    column = next_tab_stop(column, self.tab_width);
+                    pos = s.offset + 1;
+                }
+                SpecialKind::Char { len, width } => {
+                    column += match self.columns {
+                        ColumnMode::Width => width as usize,
+                        _ => 1,
+                    };
+                    pos = s.offset + len as usize;
+                }
+            }
+        }
+
+        LineCol {
+            line: line + 1,
+            column: column + offset.saturating_sub(pos),
+        }
+    }
+}
+
+fn next_tab_stop(column: usize, width: usize) -> usize {
+    (column - 1) / width * width + width + 1
+}
+
+/// A lexer that yields tokens wrapped in [`Located`](./struct.Located.html)
+///
+/// This is created with [`Lexer::located`](./struct.Lexer.html#method.located)
+pub struct PositionedLexer<T, S>
+where
+    T: PartialEq<T> + ::logos::Logos,
+{
+    lexer: Lexer<T, S, Spanned>,
+    tracker: LineTracker,
+}
+
+impl<T, S, M> Lexer<T, S, M>
+where
+    T: PartialEq<T> + ::logos::Logos,
+{
+    /// Yield the tokens wrapped with their [`Span`](./struct.Span.html) and
+    /// [`LineCol`](./struct.LineCol.html) positions
+    ///
+    /// This counts a `\t` as a single column, see
+    /// [`PositionedLexer::with_tab_width`](./struct.PositionedLexer.html#method.with_tab_width)
+    pub fn located(self) -> PositionedLexer<T, S> {
+        PositionedLexer {
+            lexer: self.spanned(),
+            tracker: LineTracker::new(),
+        }
+    }
+}
+
+impl<T, S> PositionedLexer<T, S>
+where
+    T: PartialEq<T> + ::logos::Logos,
+{
+    /// Use tab stops every `width` columns when a `\t` is found
+    pub fn with_tab_width(mut self, width: usize) -> Self {
+        self.tracker = self.tracker.with_tab_width(width);
+        self
+    }
+
+    /// Count columns with the [`ColumnMode`](./enum.ColumnMode.html)
+    pub fn with_columns(mut self, columns: ColumnMode) -> Self {
+        self.tracker = self.tracker.with_columns(columns);
+        self
+    }
+}
+
+impl<'a, T, S> Iterator for PositionedLexer<T, S>
+where
+    T: Clone + PartialEq<T>,
+    T: ::logos::Logos + ::logos::source::WithSource<S>,
+    S: ::logos::source::Source<'a>,
+{
+    type Item = Located<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let WithSpan { item, span } = self.lexer.next()?;
+        let source = &self.lexer.inner().source;
+        let start = self.tracker.advance_to(source, span.start);
+        let end = self.tracker.advance_to(source, span.end);
+        Some(Located {
+            item,
+            span,
+            start,
+            end,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+        #[regex = "\n"]
+        NewLine,
+    }
+
+    #[test]
+    fn located() {
+        let input = "ab cd\n\n  efg";
+        let located = crate::lex::<Token, _>(input)
+            .located()
+            .filter(|k| k.item == Token::Word)
+            .map(|k| ((k.start.line, k.start.column), (k.end.line, k.end.column)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            located,
+            vec![((1, 1), (1, 3)), ((1, 4), (1, 6)), ((3, 3), (3, 6))]
+        );
+    }
+
+    #[test]
+    fn line_index() {
+        let input = "ab cd\n\n  efg";
+        let index = LineIndex::new(input);
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(index.line_start(3), Some(7));
+
+        let expected = crate::lex::<Token, _>(input)
+            .located()
+            .map(|k| (k.start, k.end))
+            .collect::<Vec<_>>();
+        let got = crate::lex::<Token, _>(input)
+            .spanned()
+            .map(|k| (index.line_col(k.span.start), index.line_col(k.span.end)))
+            .collect::<Vec<_>>();
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn tab_width() {
+        let input = "\tab\n  \tcd \t\tefg";
+        let located = |width| {
+            crate::lex::<Token, _>(input)
+                .located()
+                .with_tab_width(width)
+                .filter(|k| k.item == Token::Word)
+                .map(|k| k.start.column)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(located(1), vec![2, 4, 9]);
+        assert_eq!(located(4), vec![5, 5, 13]);
+        assert_eq!(located(8), vec![9, 9, 25]);
+
+        let index = LineIndex::new(input).with_tab_width(4);
+        let got = crate::lex::<Token, _>(input)
+            .spanned()
+            .filter(|k| k.item == Token::Word)
+            .map(|k| index.line_col(k.span.start).column)
+            .collect::<Vec<_>>();
+        assert_eq!(got, located(4));
+    }
+
+    #[test]
+    fn column_mode() {
+        let input = "é ab\n日本 cd\n👨\u{200D}👩 e\u{301}f gh";
+        let columns = |mode| {
+            crate::lex::<Token, _>(input)
+                .located()
+                .with_columns(mode)
+                .filter(|k| k.item == Token::Word)
+                .map(|k| k.start.column)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(columns(ColumnMode::Bytes), vec![4, 8, 13, 16, 18]);
+        assert_eq!(columns(ColumnMode::Chars), vec![3, 4, 5, 7, 9]);
+        assert_eq!(columns(ColumnMode::Width), vec![3, 6, 4, 5, 7]);
+
+        for &mode in &[ColumnMode::Bytes, ColumnMode::Chars, ColumnMode::Width] {
+            let index = LineIndex::new(input).with_columns(mode);
+            let got = crate::lex::<Token, _>(input)
+                .spanned()
+                .filter(|k| k.item == Token::Word)
+                .map(|k| index.line_col(k.span.start).column)
+                .collect::<Vec<_>>();
+            assert_eq!(got, columns(mode));
+        }
+    }
+
+    #[test]
+    fn zwj_width() {
+        for &input in &["a\u{200D}b日x", "\u{200D}\nb c", "a\u{200D}\tb\u{200D}日 x"] {
+            let index = LineIndex::new(input).with_columns(ColumnMode::Width);
+            let mut tracker = LineTracker::new().with_columns(ColumnMode::Width);
+            for (offset, _) in input.char_indices().chain(Some((input.len(), ' '))) {
+                assert_eq!(
+                    index.line_col(offset),
+                    tracker.advance_to(&input, offset),
+                    "{:?} at {}",
+                    input,
+                    offset
+                );
+            }
+        }
+    }
+}
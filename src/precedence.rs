@@ -0,0 +1,169 @@
+use alloc::vec::Vec;
+
+/// How operators of the same precedence group together
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Assoc {
+    /// `a - b - c` is `(a - b) - c`
+    Left,
+    /// `a = b = c` is `a = (b = c)`
+    Right,
+    /// `a == b == c` isn't allowed
+    None,
+}
+
+/// Where an operator goes, relative to its operands
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Fixity {
+    /// Before its operand, like `-a`
+    Prefix,
+    /// Between its operands, like `a + b`
+    Infix,
+    /// After its operand, like `a?`
+    Postfix,
+}
+
+/// An entry in a [`PrecedenceTable`](./struct.PrecedenceTable.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Operator {
+    /// Higher precedences bind tighter
+    pub precedence: u32,
+    pub assoc: Assoc,
+    pub fixity: Fixity,
+}
+
+/// `PrecedenceTable` maps token kinds to their operator precedence, associativity and fixity
+///
+/// A kind can have an entry for each fixity, e.g. `-` as both a prefix and an infix operator
+///
+/// ```rust
+/// # use logos_iterator::{Assoc, PrecedenceTable};
+/// let table = PrecedenceTable::new()
+///     .infix('+', 1, Assoc::Left)
+///     .infix('-', 1, Assoc::Left)
+///     .infix('*', 2, Assoc::Left)
+///     .infix('^', 3, Assoc::Right)
+///     .prefix('-', 4);
+///
+/// assert_eq!(table.infix_of(&'^').map(|op| op.assoc), Some(Assoc::Right));
+/// assert_eq!(table.prefix_of(&'-').map(|op| op.precedence), Some(4));
+/// assert!(table.prefix_of(&'+').is_none());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrecedenceTable<T> {
+    operators: Vec<(T, Operator)>,
+}
+
+impl<T> Default for PrecedenceTable<T> {
+    fn default() -> Self {
+        Self { operators: vec![] }
+    }
+}
+
+impl<T> PrecedenceTable<T>
+where
+    T: PartialEq,
+{
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an infix operator
+    pub fn infix(self, kind: T, precedence: u32, assoc: Assoc) -> Self {
+        self.with(kind, precedence, assoc, Fixity::Infix)
+    }
+
+    /// Add a prefix operator
+    pub fn prefix(self, kind: T, precedence: u32) -> Self {
+        self.with(kind, precedence, Assoc::Right, Fixity::Prefix)
+    }
+
+    /// Add a postfix operator
+    pub fn postfix(self, kind: T, precedence: u32) -> Self {
+        self.with(kind, precedence, Assoc::Left, Fixity::Postfix)
+    }
+
+    /// Add an operator, replacing any previous entry for the same kind and fixity
+    pub fn with(mut self, kind: T, precedence: u32, assoc: Assoc, fixity: Fixity) -> Self {
+        self.insert(
+            kind,
+            Operator {
+                precedence,
+                assoc,
+                fixity,
+            },
+        );
+        self
+    }
+
+    /// Insert an operator, returning the previous entry for the same kind and fixity
+    pub fn insert(&mut self, kind: T, operator: Operator) -> Option<Operator> {
+        match self
+            .operators
+            .iter_mut()
+            .find(|(k, op)| *k == kind && op.fixity == operator.fixity)
+        {
+            Some((_, op)) => Some(core::mem::replace(op, operator)),
+            None => {
+                self.operators.push((kind, operator));
+                None
+            }
+        }
+    }
+
+    /// Look up the operator for `kind` with the `fixity`
+    pub fn get(&self, kind: &T, fixity: Fixity) -> Option<Operator> {
+        self.operators
+            .iter()
+            .find(|(k, op)| k == kind && op.fixity == fixity)
+            .map(|&(_, op)| op)
+    }
+
+    /// Look up the infix operator for `kind`
+    pub fn infix_of(&self, kind: &T) -> Option<Operator> {
+        self.get(kind, Fixity::Infix)
+    }
+
+    /// Look up the prefix operator for `kind`
+    pub fn prefix_of(&self, kind: &T) -> Option<Operator> {
+        self.get(kind, Fixity::Prefix)
+    }
+
+    /// Look up the postfix operator for `kind`
+    pub fn postfix_of(&self, kind: &T) -> Option<Operator> {
+        self.get(kind, Fixity::Postfix)
+    }
+}
+
+impl<T> PrecedenceTable<T> {
+    /// Iterate over the kinds and their operators, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = (&T, Operator)> + '_ {
+        self.operators.iter().map(|(k, op)| (k, *op))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert() {
+        let mut table = PrecedenceTable::new()
+            .infix('-', 1, Assoc::Left)
+            .prefix('-', 3)
+            .postfix('?', 4);
+        assert_eq!(table.iter().count(), 3);
+
+        let op = Operator {
+            precedence: 2,
+            assoc: Assoc::None,
+            fixity: Fixity::Infix,
+        };
+        assert_eq!(table.insert('-', op).map(|op| op.precedence), Some(1));
+        assert_eq!(table.infix_of(&'-'), Some(op));
+        assert_eq!(table.prefix_of(&'-').map(|op| op.precedence), Some(3));
+        assert_eq!(table.postfix_of(&'?').map(|op| op.assoc), Some(Assoc::Left));
+        assert_eq!(table.infix_of(&'?'), None);
+        assert_eq!(table.iter().count(), 3);
+    }
+}
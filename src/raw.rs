@@ -0,0 +1,155 @@
+use crate::{Span, SpannedLexer, WithSpan};
+
+/// `ResumableLexer` is a spanned lexer that can scan raw text, for things like heredocs
+///
+/// [`scan_raw`](#method.scan_raw) scans the source from an offset until a
+/// terminator that is only known at runtime (like a heredoc tag, or a custom
+/// raw string fence), produces a single token for it, and resumes lexing after it
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{ResumableLexer, Span};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "<<[A-Z]+"]
+///     Heredoc,
+///     #[regex = "[a-z]+"]
+///     Word,
+///     Raw,
+/// }
+///
+/// let source = "cat <<END\n\"not a string\nEND done";
+/// let mut lexer = ResumableLexer::new(source);
+/// let mut tokens = vec![];
+/// while let Some(token) = lexer.next() {
+///     tokens.push((token.item, &source[token.span]));
+///     if token.item == Token::Heredoc {
+///         let tag = &source[token.span.start + 2..token.span.end];
+///         let terminator = format!("\n{}", tag);
+///         let raw = lexer.scan_raw(token.span.end, &terminator, Token::Raw).unwrap();
+///         tokens.push((raw.item, &source[raw.span]));
+///     }
+/// }
+///
+/// assert_eq!(tokens, vec![
+///     (Token::Word, "cat"),
+///     (Token::Heredoc, "<<END"),
+///     (Token::Raw, "\n\"not a string\nEND"),
+///     (Token::Word, "done"),
+/// ]);
+/// ```
+pub struct ResumableLexer<'a, T>
+where
+    T: PartialEq + ::logos::Logos,
+{
+    source: &'a str,
+    offset: usize,
+    lexer: SpannedLexer<T, &'a str>,
+}
+
+impl<'a, T> ResumableLexer<'a, T>
+where
+    T: Clone + PartialEq + ::logos::Logos + ::logos::source::WithSource<&'a str>,
+{
+    /// Create a lexer at the start of `source`
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            offset: 0,
+            lexer: SpannedLexer::new(source),
+        }
+    }
+
+    /// Scan from the byte offset `from` until the end of the next `terminator`,
+    /// producing a `kind` token covering the raw text and the terminator
+    ///
+    /// Lexing resumes after the terminator. If the terminator isn't found (or
+    /// `from` isn't a valid offset), this returns `None` and the lexer is unchanged
+    pub fn scan_raw(&mut self, from: usize, terminator: &str, kind: T) -> Option<WithSpan<T>> {
+        let pos = self.source.get(from..)?.find(terminator)?;
+        let end = from + pos + terminator.len();
+        self.resume_at(end);
+        Some(WithSpan::new(kind, Span::from(from..end)))
+    }
+
+    /// Resume lexing at the byte `offset`
+    ///
+    /// # Panics
+    /// This panics if `offset` isn't on a `char` boundary of the source
+    pub fn resume_at(&mut self, offset: usize) {
+        self.lexer = SpannedLexer::new(&self.source[offset..]);
+        self.offset = offset;
+    }
+
+    /// The source being lexed
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+}
+
+impl<'a, T> Iterator for ResumableLexer<'a, T>
+where
+    T: Clone + PartialEq + ::logos::Logos + ::logos::source::WithSource<&'a str>,
+{
+    type Item = WithSpan<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset;
+        self.lexer
+            .next()
+            .map(|k| k.map_span(|span| Span::from(span.start + offset..span.end + offset)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "r#+\""]
+        RawStart,
+        #[regex = "[a-z]+"]
+        Word,
+        Raw,
+    }
+
+    #[test]
+    fn scan_raw() {
+        let source = "a r##\"b \"# c\"## d r#\"e";
+        let mut lexer = ResumableLexer::new(source);
+        let mut tokens = vec![];
+        while let Some(token) = lexer.next() {
+            tokens.push((token.item, token.span));
+            if token.item == Token::RawStart {
+                let hashes = token.span.end - token.span.start - 2;
+                let fence = format!("\"{}", "#".repeat(hashes));
+                if let Some(raw) = lexer.scan_raw(token.span.end, &fence, Token::Raw) {
+                    tokens.push((raw.item, raw.span));
+                }
+            }
+        }
+
+        let span = |start: usize, end: usize| Span::from(start..end);
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::Word, span(0, 1)),
+                (Token::RawStart, span(2, 6)),
+                (Token::Raw, span(6, 15)),
+                (Token::Word, span(16, 17)),
+                (Token::RawStart, span(18, 21)),
+                (Token::Word, span(21, 22)),
+            ]
+        );
+        assert_eq!(&source[6..15], "b \"# c\"##");
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{EditError, SpanMapper, TextEdits, WithSpan};
+use alloc::string::String;
+
+/// `Rewritten` is a source produced by [`rewrite`](./fn.rewrite.html)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rewritten {
+    /// The edited source
+    pub source: String,
+    /// The mapping from the original source to the edited source
+    pub map: SpanMapper,
+}
+
+/// Replace the text of the `tokens` of `source` where `replace` returns `Some`
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, rewrite, Span};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Ident,
+///     #[token = "="]
+///     Equal,
+/// }
+///
+/// let source = "a = bb = a";
+/// let rewritten = rewrite(source, lex::<Token, _>(source).spanned(), |k| {
+///     Some("value".to_string()).filter(|_| &source[k.span] == "a")
+/// })
+/// .unwrap();
+///
+/// assert_eq!(rewritten.source, "value = bb = value");
+/// let bb = rewritten.map.map_span(Span::from(4..6));
+/// assert_eq!(&rewritten.source[bb], "bb");
+/// ```
+pub fn rewrite<T>(
+    source: &str,
+    tokens: impl IntoIterator<Item = WithSpan<T>>,
+    mut replace: impl FnMut(&WithSpan<T>) -> Option<String>,
+) -> Result<Rewritten, EditError> {
+    let mut edits = TextEdits::new();
+    for token in tokens {
+        if let Some(text) = replace(&token) {
+            edits.replace(token.span, text);
+        }
+    }
+
+    let edited = edits.apply(source)?;
+    let map = SpanMapper::new(&edits)?;
+    Ok(Rewritten {
+        source: edited,
+        map,
+    })
+}
+
+/// Replace the comments in `tokens` of `source`, for tools that can't handle comments
+///
+/// Comments are the tokens where `is_comment` returns `true`. Each comment is
+/// replaced with a space so the tokens around it stay separate, or with its
+/// line endings if it has any, so the line numbers stay the same. The
+/// [`SpanMapper`](./struct.SpanMapper.html) maps offsets in both directions
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, strip_comments, Span};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Ident,
+///     #[regex = "/\\*([^*]|\\*[^/])*\\*/"]
+///     Comment,
+///     #[token = "\n"]
+///     NewLine,
+/// }
+///
+/// let source = "a/* one */b /* two\nlines */ c";
+/// let stripped = strip_comments(source, lex::<Token, _>(source).spanned(), |k| *k == Token::Comment)
+///     .unwrap();
+/// assert_eq!(stripped.source, "a b \n c");
+///
+/// // the `c` in the stripped source
+/// assert_eq!(stripped.map.unmap_span(Span::from(6..7)), Span::from(28..29));
+/// ```
+pub fn strip_comments<T>(
+    source: &str,
+    tokens: impl IntoIterator<Item = WithSpan<T>>,
+    is_comment: impl Fn(&T) -> bool,
+) -> Result<Rewritten, EditError> {
+    rewrite(source, tokens, |k| {
+        if !is_comment(&k.item) {
+            return None;
+        }
+        let text = source.get(k.span.start..k.span.end).unwrap_or("");
+        let endings = text
+            .split_inclusive('\n')
+            .filter_map(|line| line.strip_suffix('\n'))
+            .map(|line| if line.ends_with('\r') { "\r\n" } else { "\n" })
+            .collect::<String>();
+        if endings.is_empty() {
+            Some(" ".into())
+        } else {
+            Some(endings)
+        }
+    })
+}
+
+/// Produce the edits that rename every occurrence of the identifier `target` to `new_name`
+///
+/// Identifiers are the `tokens` where `is_ident` returns `true`, and they are
+/// compared by their slices of `source`
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, rename};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Ident,
+///     #[regex = "\"[^\"]*\""]
+///     String,
+/// }
+///
+/// let source = r#"x xy "x" x"#;
+/// let tokens = lex::<Token, _>(source).spanned();
+/// let edits = rename(source, tokens, |k| *k == Token::Ident, "x", "z");
+///
+/// assert_eq!(edits.len(), 2);
+/// assert_eq!(edits.apply(source).unwrap(), r#"z xy "x" z"#);
+/// ```
+pub fn rename<T>(
+    source: &str,
+    tokens: impl IntoIterator<Item = WithSpan<T>>,
+    is_ident: impl Fn(&T) -> bool,
+    target: &str,
+    new_name: &str,
+) -> TextEdits {
+    let mut edits = TextEdits::new();
+    for token in tokens {
+        if is_ident(&token.item) && source.get(token.span.start..token.span.end) == Some(target) {
+            edits.replace(token.span, new_name);
+        }
+    }
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Span;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn span_map() {
+        let source = "ab cd ef";
+        let tokens = [(0..2), (3..5), (6..8)]
+            .iter()
+            .map(|range| WithSpan::new((), Span::from(range.clone())));
+        let rewritten = rewrite(source, tokens, |k| match k.span.start {
+            0 => Some("x".into()),
+            3 => Some("yyyy".into()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(rewritten.source, "x yyyy ef");
+
+        let map = &rewritten.map;
+        let offsets = (0..=source.len())
+            .map(|i| map.map_offset(i))
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, vec![0, 1, 1, 2, 3, 6, 7, 8, 9]);
+
+        assert_eq!(map.map_span(Span::from(6..8)), Span::from(7..9));
+        // the replaced tokens map to all of their replacements
+        assert_eq!(map.map_span(Span::from(3..5)), Span::from(2..6));
+        assert_eq!(&rewritten.source[map.map_span(Span::from(0..2))], "x");
+        assert_eq!(&rewritten.source[map.map_span(Span::from(3..5))], "yyyy");
+        assert_eq!(map.try_map_span(Span::from(3..5)), Some(Span::from(2..6)));
+        assert_eq!(map.map_span(Span::from(0..8)), Span::from(0..9));
+        assert_eq!(map.map_span(Span::from(2..2)), Span::from(1..1));
+        assert_eq!(map.iter().count(), 2);
+
+        let offsets = (0..=rewritten.source.len())
+            .map(|i| map.unmap_offset(i))
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, vec![0, 2, 3, 4, 5, 5, 5, 6, 7, 8]);
+        assert_eq!(map.unmap_span(Span::from(2..6)), Span::from(3..5));
+        assert_eq!(map.unmap_span(Span::from(3..4)), Span::from(4..5));
+        assert_eq!(map.unmap_span(Span::from(7..9)), Span::from(6..8));
+    }
+}
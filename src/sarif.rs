@@ -0,0 +1,158 @@
+use crate::export::json_string;
+use crate::{ColumnMode, Diagnostic, LineIndex};
+use alloc::{string::String, vec::Vec};
+
+/// `Sarif` collects [`Diagnostic`](./struct.Diagnostic.html)s into a
+/// [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log,
+/// like the ones that GitHub code scanning accepts
+///
+/// Each code is a rule, and each diagnostic is a result with a region found
+/// from its span. Columns are counted in `char`s
+///
+/// ```rust
+/// # use logos_iterator::{Diagnostic, Sarif, Span};
+/// let mut sarif = Sarif::new("lint");
+/// sarif.add(
+///     "src/main.txt",
+///     "a $ b",
+///     &[Diagnostic::error("unknown token", Span::from(2..3)).with_code("E0001")],
+/// );
+///
+/// let log = sarif.to_json();
+/// assert!(log.contains(r#""rules":[{"id":"E0001"}]"#));
+/// assert!(log.contains(r#""region":{"startLine":1,"startColumn":3,"endLine":1,"endColumn":4,"byteOffset":2,"byteLength":1}"#));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Sarif {
+    tool: String,
+    rules: Vec<String>,
+    results: Vec<String>,
+}
+
+impl Sarif {
+    /// Create an empty log for the tool `tool`
+    pub fn new(tool: impl Into<String>) -> Self {
+        Self {
+            tool: tool.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Add the `diagnostics` about the file at the URI `file_name`, with `source`
+    pub fn add(&mut self, file_name: &str, source: &str, diagnostics: &[Diagnostic]) -> &mut Self {
+        let index = LineIndex::new(source).with_columns(ColumnMode::Chars);
+        for diagnostic in diagnostics {
+            let rule = match &diagnostic.code {
+                Some(code) => {
+                    if !self.rules.contains(code) {
+                        self.rules.push(code.clone());
+                    }
+                    format!(r#""ruleId":{},"#, json_string(code))
+                }
+                None => String::new(),
+            };
+
+            let span = diagnostic.span;
+            let (start, end) = (index.line_col(span.start), index.line_col(span.end));
+            let mut text = diagnostic.message.clone();
+            for (kind, s) in diagnostic.children() {
+                text.push_str(&format!("\n{}: {}", kind, s));
+            }
+
+            self.results.push(format!(
+                concat!(
+                    r#"{{{}"level":"{}","message":{{"text":{}}},"locations":[{{"physicalLocation":{{"#,
+                    r#""artifactLocation":{{"uri":{}}},"region":{{"startLine":{},"startColumn":{},"#,
+                    r#""endLine":{},"endColumn":{},"byteOffset":{},"byteLength":{}}}}}}}]}}"#
+                ),
+                rule,
+                diagnostic.severity,
+                json_string(&text),
+                json_string(file_name),
+                start.line,
+                start.column,
+                end.line,
+                end.column,
+                span.start,
+                span.end.saturating_sub(span.start)
+            ));
+        }
+        self
+    }
+
+    /// The number of results
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Whether there are no results
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Convert the log to JSON
+    pub fn to_json(&self) -> String {
+        let rules = self
+            .rules
+            .iter()
+            .map(|id| format!(r#"{{"id":{}}}"#, json_string(id)))
+            .collect::<Vec<_>>();
+        format!(
+            concat!(
+                r#"{{"$schema":"https://json.schemastore.org/sarif-2.1.0.json","version":"2.1.0","#,
+                r#""runs":[{{"tool":{{"driver":{{"name":{},"rules":[{}]}}}},"#,
+                r#""columnKind":"unicodeCodePoints","results":[{}]}}]}}"#
+            ),
+            json_string(&self.tool),
+            rules.join(","),
+            self.results.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Span;
+
+    #[test]
+    fn log() {
+        let mut sarif = Sarif::new("lint");
+        assert!(sarif.is_empty());
+        assert_eq!(
+            sarif.to_json(),
+            concat!(
+                r#"{"$schema":"https://json.schemastore.org/sarif-2.1.0.json","version":"2.1.0","#,
+                r#""runs":[{"tool":{"driver":{"name":"lint","rules":[]}},"#,
+                r#""columnKind":"unicodeCodePoints","results":[]}]}"#
+            )
+        );
+
+        sarif
+            .add(
+                "a.txt",
+                "éa\nb",
+                &[
+                    Diagnostic::warning("one", Span::from(2..3)).with_code("W1"),
+                    Diagnostic::error("two", Span::from(4..5)).with_help("fix it"),
+                ],
+            )
+            .add(
+                "b.txt",
+                "c",
+                &[Diagnostic::warning("three", Span::from(0..1)).with_code("W1")],
+            );
+        assert_eq!(sarif.len(), 3);
+
+        let log = sarif.to_json();
+        assert!(log.contains(r#""rules":[{"id":"W1"}]"#));
+        assert!(log.contains(concat!(
+            r#"{"ruleId":"W1","level":"warning","message":{"text":"one"},"#,
+            r#""locations":[{"physicalLocation":{"artifactLocation":{"uri":"a.txt"},"#,
+            r#""region":{"startLine":1,"startColumn":2,"endLine":1,"endColumn":3,"#,
+            r#""byteOffset":2,"byteLength":1}}}]}"#
+        )));
+        assert!(log.contains(r#"{"level":"error","message":{"text":"two\nhelp: fix it"}"#));
+        assert!(log.contains(r#"{"uri":"b.txt"}"#));
+    }
+}
@@ -0,0 +1,100 @@
+use alloc::vec::Vec;
+
+/// `TokenSet` is a small set of token kinds
+///
+/// This is used for things like synchronization points during error recovery,
+/// or the tokens that were expected at some position
+///
+/// ```rust
+/// # use logos_iterator::TokenSet;
+/// let set = TokenSet::new([';', '}']);
+/// assert!(set.contains(&';'));
+/// assert!(!set.contains(&'{'));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenSet<T> {
+    kinds: Vec<T>,
+}
+
+impl<T> Default for TokenSet<T> {
+    fn default() -> Self {
+        Self { kinds: vec![] }
+    }
+}
+
+impl<T> TokenSet<T>
+where
+    T: PartialEq,
+{
+    /// Create a set of the `kinds`
+    pub fn new(kinds: impl IntoIterator<Item = T>) -> Self {
+        kinds.into_iter().collect()
+    }
+
+    /// Add `kind` to the set, returning whether it wasn't already in the set
+    pub fn insert(&mut self, kind: T) -> bool {
+        if self.contains(&kind) {
+            return false;
+        }
+        self.kinds.push(kind);
+        true
+    }
+
+    /// Whether `kind` is in the set
+    pub fn contains(&self, kind: &T) -> bool {
+        self.kinds.contains(kind)
+    }
+
+    /// Add all of the kinds in `other` to the set
+    pub fn union(mut self, other: Self) -> Self {
+        self.extend(other.kinds);
+        self
+    }
+}
+
+impl<T> TokenSet<T> {
+    /// The number of kinds in the set
+    pub fn len(&self) -> usize {
+        self.kinds.len()
+    }
+
+    /// Whether the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+    }
+
+    /// Iterate over the kinds, in the order they were added
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.kinds.iter()
+    }
+}
+
+impl<T> Extend<T> for TokenSet<T>
+where
+    T: PartialEq,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for kind in iter {
+            self.insert(kind);
+        }
+    }
+}
+
+impl<T> core::iter::FromIterator<T> for TokenSet<T>
+where
+    T: PartialEq,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TokenSet<T> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.kinds.iter()
+    }
+}
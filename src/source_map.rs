@@ -0,0 +1,384 @@
+use crate::{LineIndex, Span};
+use alloc::{string::String, vec::Vec};
+
+/// `FileId` identifies a file in a [`SourceMap`](./struct.SourceMap.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(u32);
+
+impl FileId {
+    /// The index of the file, in the order the files were added
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// `SourceFile` is a file in a [`SourceMap`](./struct.SourceMap.html)
+#[derive(Clone, Debug)]
+pub struct SourceFile {
+    name: String,
+    source: String,
+    index: LineIndex,
+}
+
+impl SourceFile {
+    /// The name of the file
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The source of the file
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The [`LineIndex`](./struct.LineIndex.html) of the source
+    pub fn line_index(&self) -> &LineIndex {
+        &self.index
+    }
+}
+
+/// `SourceMap` owns a set of named sources, identified by [`FileId`](./struct.FileId.html)s
+///
+/// It can be indexed by a [`FileSpan`](./struct.FileSpan.html), to get the text
+/// of that file at the span
+///
+/// ```rust
+/// # use logos_iterator::{SourceMap, Span};
+/// let mut map = SourceMap::new();
+/// let main = map.add("main.txt", "hello world");
+/// let lib = map.add("lib.txt", "one\ntwo");
+///
+/// let span = Span::from(4..7).in_file(lib);
+/// assert_eq!(&map[span], "two");
+/// assert_eq!(map.name(span.file), "lib.txt");
+/// assert_eq!(map.line_col(span).line, 2);
+/// assert_eq!(map.get_span(Span::from(6..20).in_file(main)), None);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    /// Create an empty source map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file named `name` with the `source`
+    pub fn add(&mut self, name: impl Into<String>, source: impl Into<String>) -> FileId {
+        let source = source.into();
+        let id = FileId(self.files.len() as u32);
+        self.files.push(SourceFile {
+            name: name.into(),
+            index: LineIndex::new(&source),
+            source,
+        });
+        id
+    }
+
+    /// Get the file for `id`, if it is in this map
+    pub fn get(&self, id: FileId) -> Option<&SourceFile> {
+        self.files.get(id.index())
+    }
+
+    /// Get the file for `id`
+    ///
+    /// # Panics
+    /// This panics if `id` isn't from this map
+    pub fn file(&self, id: FileId) -> &SourceFile {
+        &self.files[id.index()]
+    }
+
+    /// The name of the file for `id`
+    pub fn name(&self, id: FileId) -> &str {
+        self.file(id).name()
+    }
+
+    /// The source of the file for `id`
+    pub fn source(&self, id: FileId) -> &str {
+        self.file(id).source()
+    }
+
+    /// Get the text at `span`, if the file and span are valid
+    pub fn get_span(&self, span: FileSpan) -> Option<&str> {
+        self.get(span.file)?.source.get(span.start..span.end)
+    }
+
+    /// The line and column of the start of `span`
+    pub fn line_col(&self, span: FileSpan) -> crate::LineCol {
+        self.file(span.file).index.line_col(span.start)
+    }
+
+    /// The number of files
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether there are no files
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Iterate over the files and their ids, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = (FileId, &SourceFile)> + '_ {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| (FileId(i as u32), file))
+    }
+}
+
+impl core::ops::Index<FileSpan> for SourceMap {
+    type Output = str;
+    fn index(&self, index: FileSpan) -> &Self::Output {
+        &self.source(index.file)[index.span()]
+    }
+}
+
+impl crate::SpanResolver<FileSpan> for SourceMap {
+    type Output = str;
+    fn try_resolve(&self, span: FileSpan) -> Option<&Self::Output> {
+        self.get_span(span)
+    }
+    fn checked_resolve(&self, span: FileSpan) -> Result<&Self::Output, crate::SliceError> {
+        let file = self.get(span.file).ok_or(crate::SliceError::NotInSource)?;
+        crate::slice_str(&file.source, Span::from(span.start..span.end))
+    }
+}
+
+/// `FileSpan` is a [`Span`](./struct.Span.html) in a file of a [`SourceMap`](./struct.SourceMap.html)
+///
+/// This can be used as the span of a [`WithSpan`](./struct.WithSpan.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileSpan {
+    pub file: FileId,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl FileSpan {
+    /// Create a span in `file`
+    pub fn new(file: FileId, span: Span) -> Self {
+        Self {
+            file,
+            start: span.start,
+            end: span.end,
+        }
+    }
+
+    /// The span, without the file
+    pub fn span(self) -> Span {
+        Span::from(self.start..self.end)
+    }
+}
+
+impl Span {
+    /// This span in `file`, as a [`FileSpan`](./struct.FileSpan.html)
+    pub fn in_file(self, file: FileId) -> FileSpan {
+        FileSpan::new(file, self)
+    }
+}
+
+impl From<FileSpan> for Span {
+    fn from(span: FileSpan) -> Self {
+        span.span()
+    }
+}
+
+/// `Segment` is a region of a [`CompositeSource`](./struct.CompositeSource.html) and where it came from
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    /// The span of the region, in the composite source
+    pub span: Span,
+    /// The span of the region, in its original file
+    pub origin: FileSpan,
+}
+
+/// `CompositeSource` concatenates regions of files into one source, like `#include` does
+///
+/// The composite source can be lexed as a whole, and any span of it can be
+/// resolved back to its original file with [`resolve_span`](#method.resolve_span)
+///
+/// ```rust
+/// # use logos_iterator::{CompositeSource, SourceMap, Span};
+/// let mut map = SourceMap::new();
+/// let main = map.add("main.c", "int a;\n#include \"b.h\"\nint c;\n");
+/// let header = map.add("b.h", "int b;\n");
+///
+/// let mut composite = CompositeSource::new();
+/// composite
+///     .push(&map, Span::from(0..7).in_file(main))
+///     .push(&map, Span::from(0..7).in_file(header))
+///     .push(&map, Span::from(22..29).in_file(main));
+/// assert_eq!(composite.source(), "int a;\nint b;\nint c;\n");
+///
+/// let c = composite.resolve_span(Span::from(18..19)).unwrap();
+/// assert_eq!((map.name(c.file), &map[c]), ("main.c", "c"));
+/// assert_eq!(map.line_col(c).line, 3);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CompositeSource {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl CompositeSource {
+    /// Create an empty composite source
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the text at `span` from the `map`
+    ///
+    /// # Panics
+    /// This panics if the span isn't valid for its file
+    pub fn push(&mut self, map: &SourceMap, span: FileSpan) -> &mut Self {
+        let text = &map[span];
+        let start = self.source.len();
+        self.source.push_str(text);
+        self.segments.push(Segment {
+            span: Span::from(start..self.source.len()),
+            origin: span,
+        });
+        self
+    }
+
+    /// Append all of the file `id` from the `map`
+    pub fn push_file(&mut self, map: &SourceMap, id: FileId) -> &mut Self {
+        let len = map.source(id).len();
+        self.push(map, Span::from(0..len).in_file(id))
+    }
+
+    /// The concatenated source
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The segments, in order
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Find the segment containing the byte `offset`
+    ///
+    /// An offset at the end of a segment is in the next segment, unless it's the last one
+    pub fn segment_at(&self, offset: usize) -> Option<&Segment> {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.span.end <= offset);
+        self.segments
+            .get(index)
+            .filter(|segment| segment.span.start <= offset)
+            .or_else(|| self.segments.last().filter(|s| s.span.end == offset))
+    }
+
+    /// Resolve an `offset` of the composite source to its original file and offset
+    pub fn resolve(&self, offset: usize) -> Option<(FileId, usize)> {
+        let segment = self.segment_at(offset)?;
+        Some((
+            segment.origin.file,
+            segment.origin.start + offset - segment.span.start,
+        ))
+    }
+
+    /// Resolve a `span` of the composite source to its original file, if it's within one segment
+    pub fn resolve_span(&self, span: Span) -> Option<FileSpan> {
+        let segment = self.segment_at(span.start)?;
+        if span.end > segment.span.end {
+            return None;
+        }
+        let start = segment.origin.start + span.start - segment.span.start;
+        let end = segment.origin.start + span.end - segment.span.start;
+        Some(Span::from(start..end).in_file(segment.origin.file))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WithSpan;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+    }
+
+    #[test]
+    fn file_spans() {
+        let mut map = SourceMap::new();
+        let a = map.add("a", "one two");
+        let b = map.add("b", "three");
+        assert_eq!(map.len(), 2);
+
+        let words = map
+            .iter()
+            .flat_map(|(id, file)| {
+                crate::lex::<Token, _>(file.source())
+                    .spanned()
+                    .map(move |k| k.map_span(|span| span.in_file(id)))
+            })
+            .collect::<Vec<WithSpan<_, _>>>();
+
+        let text = words
+            .iter()
+            .map(|k| (map.name(k.span.file), &map[k.span]))
+            .collect::<Vec<_>>();
+        assert_eq!(text, vec![("a", "one"), ("a", "two"), ("b", "three")]);
+        assert_eq!(words[1].span, FileSpan::new(a, Span::from(4..7)));
+        assert_eq!(Span::from(words[2].span), Span::from(0..5));
+        assert!(map.get(b).is_some());
+
+        use crate::SpanResolver as _;
+        assert_eq!(map.resolve(words[2].span), "three");
+        assert_eq!(map.try_resolve(Span::from(4..9).in_file(a)), None);
+        assert_eq!(map.try_resolve(Span::from(0..1).in_file(FileId(2))), None);
+        assert_eq!(
+            map.checked_resolve(Span::from(0..1).in_file(FileId(2))),
+            Err(crate::SliceError::NotInSource)
+        );
+        assert_eq!(
+            map.checked_resolve(Span::from(4..9).in_file(a)),
+            Err(crate::SliceError::OutOfBounds {
+                span: Span::from(4..9),
+                len: 7
+            })
+        );
+    }
+
+    #[test]
+    fn composite() {
+        let mut map = SourceMap::new();
+        let a = map.add("a", "ab");
+        let b = map.add("b", "cde");
+        let mut composite = CompositeSource::new();
+        composite.push_file(&map, a).push_file(&map, b);
+        assert_eq!(composite.source(), "abcde");
+
+        let resolved = (0..=5).map(|i| composite.resolve(i)).collect::<Vec<_>>();
+        assert_eq!(
+            resolved,
+            vec![
+                Some((a, 0)),
+                Some((a, 1)),
+                Some((b, 0)),
+                Some((b, 1)),
+                Some((b, 2)),
+                Some((b, 3))
+            ]
+        );
+        assert_eq!(composite.resolve(6), None);
+        assert_eq!(composite.resolve_span(Span::from(1..3)), None);
+        assert_eq!(
+            composite.resolve_span(Span::from(2..2)),
+            Some(Span::from(0..0).in_file(b))
+        );
+        assert_eq!(CompositeSource::new().resolve(0), None);
+    }
+}
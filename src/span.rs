@@ -0,0 +1,560 @@
+use crate::LineIndex;
+use alloc::{string::String, vec::Vec};
+
+/// `WithSpan` wraps something with a [`Span`](./struct.Span.html)
+///
+/// The span type `S` can be changed to carry something other than a `Span`,
+/// such as a span that also knows which file it is in
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WithSpan<T, S = Span> {
+    pub item: T,
+    pub span: S,
+}
+
+impl<T, S> WithSpan<T, S> {
+    /// Wrap `item` with [`span`](./struct.Span.html)
+    pub fn new(item: T, span: S) -> Self {
+        Self { item, span }
+    }
+
+    /// Map the item with `f`, keeping the same span
+    pub fn map<U, F>(self, f: F) -> WithSpan<U, S>
+    where
+        F: FnOnce(T) -> U,
+    {
+        WithSpan::new(f(self.item), self.span)
+    }
+
+    /// Map the span with `f`, keeping the same item
+    pub fn map_span<U, F>(self, f: F) -> WithSpan<T, U>
+    where
+        F: FnOnce(S) -> U,
+    {
+        WithSpan::new(self.item, f(self.span))
+    }
+
+    /// Borrow the item, keeping the same span
+    pub fn as_ref(&self) -> WithSpan<&T, S>
+    where
+        S: Clone,
+    {
+        WithSpan::new(&self.item, self.span.clone())
+    }
+
+    /// Mutably borrow the item, keeping the same span
+    pub fn as_mut(&mut self) -> WithSpan<&mut T, S>
+    where
+        S: Clone,
+    {
+        WithSpan::new(&mut self.item, self.span.clone())
+    }
+
+    /// Split this into the item and its span
+    pub fn into_parts(self) -> (T, S) {
+        (self.item, self.span)
+    }
+}
+
+impl<T, S> core::ops::Deref for WithSpan<T, S> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+/// `Span` represents a `start`..`end` range
+///
+/// Spans are ordered by their `start`, then by their `end`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// Create a span from `start` to `end`
+    ///
+    /// In debug builds, this panics if `end` is before `start`. Use
+    /// [`try_new`](#method.try_new) to check it instead
+    ///
+    /// ```rust
+    /// # use logos_iterator::{SliceError, Span};
+    /// assert_eq!(Span::new(1, 3), Span::from(1..3));
+    /// assert_eq!(Span::empty(2), Span::new(2, 2));
+    /// assert_eq!(Span::try_new(3, 1), Err(SliceError::Reversed { span: Span { start: 3, end: 1 } }));
+    /// ```
+    pub const fn new(start: usize, end: usize) -> Self {
+        debug_assert!(start <= end, "span ends before it starts");
+        Self { start, end }
+    }
+
+    /// Create a span from `start` to `end`, if `end` isn't before `start`
+    pub fn try_new(start: usize, end: usize) -> Result<Self, SliceError> {
+        let span = Self { start, end };
+        if end < start {
+            return Err(SliceError::Reversed { span });
+        }
+        Ok(span)
+    }
+
+    /// Create an empty span at `offset`
+    pub const fn empty(offset: usize) -> Self {
+        Self {
+            start: offset,
+            end: offset,
+        }
+    }
+
+    /// Join this span with `other`, producing a span that covers both of them
+    pub fn join(self, other: Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+
+    /// The slice of `source` that this span covers
+    ///
+    /// This works for anything that is a [`SpanResolver`](./trait.SpanResolver.html),
+    /// such as `str` and `[u8]`. It panics if the span isn't in the source
+    ///
+    /// ```rust
+    /// # use logos_iterator::Span;
+    /// let span = Span::from(3..5);
+    /// assert_eq!(span.slice_of("ab cd"), "cd");
+    /// assert_eq!(span.slice_of(&b"ab cd"[..]), b"cd");
+    /// assert_eq!(span.try_slice_of("ab"), None);
+    /// ```
+    pub fn slice_of<R>(self, source: &R) -> &R::Output
+    where
+        R: SpanResolver + ?Sized,
+    {
+        source.resolve(self)
+    }
+
+    /// The slice of `source` that this span covers, if it's in the source
+    pub fn try_slice_of<R>(self, source: &R) -> Option<&R::Output>
+    where
+        R: SpanResolver + ?Sized,
+    {
+        source.try_resolve(self)
+    }
+
+    /// The slice of `source` that this span covers, or why it isn't in the source
+    ///
+    /// ```rust
+    /// # use logos_iterator::{SliceError, Span};
+    /// assert_eq!(Span::from(0..2).checked_slice_of("é"), Ok("é"));
+    /// assert_eq!(
+    ///     Span::from(0..1).checked_slice_of("é"),
+    ///     Err(SliceError::NotCharBoundary { span: Span::from(0..1), offset: 1 })
+    /// );
+    /// assert_eq!(
+    ///     Span::from(1..4).checked_slice_of("é"),
+    ///     Err(SliceError::OutOfBounds { span: Span::from(1..4), len: 2 })
+    /// );
+    /// ```
+    pub fn checked_slice_of<R>(self, source: &R) -> Result<&R::Output, SliceError>
+    where
+        R: SpanResolver + ?Sized,
+    {
+        source.checked_resolve(self)
+    }
+
+    /// Display this span as `line:column-line:column`, using `index` to find the
+    /// positions
+    pub fn display_with(self, index: &LineIndex) -> SpanDisplay<'_> {
+        SpanDisplay { span: self, index }
+    }
+}
+
+impl core::fmt::Display for Span {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// Displays a [`Span`](./struct.Span.html) as `line:column-line:column`
+///
+/// This is created with [`Span::display_with`](./struct.Span.html#method.display_with)
+#[derive(Copy, Clone, Debug)]
+pub struct SpanDisplay<'a> {
+    span: Span,
+    index: &'a LineIndex,
+}
+
+impl core::fmt::Display for SpanDisplay<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let start = self.index.line_col(self.span.start);
+        let end = self.index.line_col(self.span.end);
+        write!(
+            f,
+            "{}:{}-{}:{}",
+            start.line, start.column, end.line, end.column
+        )
+    }
+}
+
+impl From<core::ops::Range<usize>> for Span {
+    fn from(range: core::ops::Range<usize>) -> Self {
+        Self {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+impl From<Span> for core::ops::Range<usize> {
+    fn from(span: Span) -> Self {
+        span.start..span.end
+    }
+}
+
+impl core::ops::RangeBounds<usize> for Span {
+    fn start_bound(&self) -> core::ops::Bound<&usize> {
+        core::ops::Bound::Included(&self.start)
+    }
+    fn end_bound(&self) -> core::ops::Bound<&usize> {
+        core::ops::Bound::Excluded(&self.end)
+    }
+}
+
+impl core::ops::Index<Span> for str {
+    type Output = str;
+    fn index(&self, index: Span) -> &Self::Output {
+        self.index(index.start..index.end)
+    }
+}
+
+impl core::ops::Index<Span> for String {
+    type Output = str;
+    fn index(&self, index: Span) -> &Self::Output {
+        self.index(index.start..index.end)
+    }
+}
+
+impl core::ops::Index<Span> for [u8] {
+    type Output = [u8];
+    fn index(&self, index: Span) -> &Self::Output {
+        self.index(index.start..index.end)
+    }
+}
+
+impl core::ops::Index<Span> for Vec<u8> {
+    type Output = [u8];
+    fn index(&self, index: Span) -> &Self::Output {
+        self.index(index.start..index.end)
+    }
+}
+
+/// `SliceError` is why a span couldn't be used to slice a source
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SliceError {
+    /// The span ends before it starts
+    Reversed { span: Span },
+    /// The span ends past the end of the source, which is `len` bytes long
+    OutOfBounds { span: Span, len: usize },
+    /// The `offset` of the span isn't on a `char` boundary of the source
+    NotCharBoundary { span: Span, offset: usize },
+    /// The span isn't in the source for some other reason, like being in
+    /// another file
+    NotInSource,
+}
+
+impl core::fmt::Display for SliceError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Reversed { span } => write!(f, "span {} ends before it starts", span),
+            Self::OutOfBounds { span, len } => write!(
+                f,
+                "span {} is out of bounds of the source, which is {} bytes long",
+                span, len
+            ),
+            Self::NotCharBoundary { span, offset } => write!(
+                f,
+                "span {} isn't on a char boundary at offset {}",
+                span, offset
+            ),
+            Self::NotInSource => f.write_str("span isn't in the source"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SliceError {}
+
+/// Get the slice of `source` at `span`, or why it isn't in the source
+///
+/// ```rust
+/// # use logos_iterator::{slice_str, SliceError, Span};
+/// assert_eq!(slice_str("abc", Span::from(1..3)), Ok("bc"));
+/// assert_eq!(
+///     slice_str("abc", Span { start: 2, end: 1 }),
+///     Err(SliceError::Reversed { span: Span { start: 2, end: 1 } })
+/// );
+/// ```
+pub fn slice_str(source: &str, span: Span) -> Result<&str, SliceError> {
+    slice_bytes(source.as_bytes(), span)?;
+    for offset in [span.start, span.end] {
+        if !source.is_char_boundary(offset) {
+            return Err(SliceError::NotCharBoundary { span, offset });
+        }
+    }
+    Ok(&source[span.start..span.end])
+}
+
+/// Get the slice of `source` at `span`, or why it isn't in the source
+pub fn slice_bytes(source: &[u8], span: Span) -> Result<&[u8], SliceError> {
+    if span.end < span.start {
+        return Err(SliceError::Reversed { span });
+    }
+    source
+        .get(span.start..span.end)
+        .ok_or(SliceError::OutOfBounds {
+            span,
+            len: source.len(),
+        })
+}
+
+/// `GetSpan` is the checked version of indexing by a [`Span`](./struct.Span.html)
+///
+/// This returns `None` rather than panicking when the span is out of bounds
+/// (or not on a `char` boundary for `str`)
+pub trait GetSpan {
+    /// The type of the slice that the span resolves to
+    type Output: ?Sized;
+
+    /// Get the slice at `span`, if it's valid
+    fn get_span(&self, span: Span) -> Option<&Self::Output>;
+
+    /// Get the slice at `span`, or why it isn't valid
+    fn checked_span(&self, span: Span) -> Result<&Self::Output, SliceError> {
+        self.get_span(span).ok_or(SliceError::NotInSource)
+    }
+}
+
+impl GetSpan for str {
+    type Output = str;
+    fn get_span(&self, span: Span) -> Option<&Self::Output> {
+        self.get(span.start..span.end)
+    }
+    fn checked_span(&self, span: Span) -> Result<&Self::Output, SliceError> {
+        slice_str(self, span)
+    }
+}
+
+impl GetSpan for String {
+    type Output = str;
+    fn get_span(&self, span: Span) -> Option<&Self::Output> {
+        self.get(span.start..span.end)
+    }
+    fn checked_span(&self, span: Span) -> Result<&Self::Output, SliceError> {
+        slice_str(self, span)
+    }
+}
+
+impl GetSpan for [u8] {
+    type Output = [u8];
+    fn get_span(&self, span: Span) -> Option<&Self::Output> {
+        self.get(span.start..span.end)
+    }
+    fn checked_span(&self, span: Span) -> Result<&Self::Output, SliceError> {
+        slice_bytes(self, span)
+    }
+}
+
+impl GetSpan for Vec<u8> {
+    type Output = [u8];
+    fn get_span(&self, span: Span) -> Option<&Self::Output> {
+        self.get(span.start..span.end)
+    }
+    fn checked_span(&self, span: Span) -> Result<&Self::Output, SliceError> {
+        slice_bytes(self, span)
+    }
+}
+
+/// `SpanResolver` resolves spans of type `S` to the text they cover
+///
+/// This is implemented with [`Span`](./struct.Span.html) for everything that is
+/// [`GetSpan`](./trait.GetSpan.html), and with [`FileSpan`](./struct.FileSpan.html)
+/// for a [`SourceMap`](./struct.SourceMap.html)
+pub trait SpanResolver<S = Span> {
+    /// The type of the slice that a span resolves to
+    type Output: ?Sized;
+
+    /// Get the slice at `span`, if it's valid
+    fn try_resolve(&self, span: S) -> Option<&Self::Output>;
+
+    /// Get the slice at `span`, or why it isn't valid
+    fn checked_resolve(&self, span: S) -> Result<&Self::Output, SliceError> {
+        self.try_resolve(span).ok_or(SliceError::NotInSource)
+    }
+
+    /// Get the slice at `span`
+    ///
+    /// This panics with the [`SliceError`](./enum.SliceError.html) if the span isn't valid
+    fn resolve(&self, span: S) -> &Self::Output {
+        match self.checked_resolve(span) {
+            Ok(slice) => slice,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<T> SpanResolver for T
+where
+    T: GetSpan + ?Sized,
+{
+    type Output = T::Output;
+    fn try_resolve(&self, span: Span) -> Option<&Self::Output> {
+        self.get_span(span)
+    }
+    fn checked_resolve(&self, span: Span) -> Result<&Self::Output, SliceError> {
+        self.checked_span(span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn span_index() {
+        let s = "this is a test";
+        let span = Span { start: 5, end: 9 };
+        assert_eq!("is a", &s[span]);
+
+        let s = String::from(s);
+        let span = Span { start: 5, end: 9 };
+        assert_eq!("is a", &s[span]);
+
+        let s = s.into_bytes();
+        assert_eq!(b"is a", &s[span]);
+        assert_eq!(b"is a", &s[..][span]);
+    }
+
+    #[test]
+    fn span_get() {
+        let s = "this is é test";
+        assert_eq!(s.get_span(Span { start: 5, end: 10 }), Some("is é"));
+        assert_eq!(s.get_span(Span { start: 5, end: 9 }), None);
+        assert_eq!(s.get_span(Span { start: 5, end: 30 }), None);
+
+        let s = s.as_bytes();
+        assert_eq!(s.get_span(Span { start: 5, end: 9 }), Some(&b"is \xC3"[..]));
+        assert_eq!(s.to_vec().get_span(Span { start: 5, end: 30 }), None);
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(Span::try_new(2, 2), Ok(Span::empty(2)));
+        assert_eq!(Span::try_new(1, 4), Ok(Span::new(1, 4)));
+        assert!(Span::try_new(4, 1).is_err());
+
+        #[cfg(debug_assertions)]
+        assert!(std::panic::catch_unwind(|| Span::new(4, 1)).is_err());
+    }
+
+    #[test]
+    fn checked() {
+        let s = "this is é test";
+        let span = |start, end| Span { start, end };
+        assert_eq!(s.checked_span(span(8, 10)), Ok("é"));
+        assert_eq!(
+            s.checked_span(span(8, 9)),
+            Err(SliceError::NotCharBoundary {
+                span: span(8, 9),
+                offset: 9
+            })
+        );
+        assert_eq!(
+            String::from(s).checked_span(span(9, 10)),
+            Err(SliceError::NotCharBoundary {
+                span: span(9, 10),
+                offset: 9
+            })
+        );
+        assert_eq!(
+            s.as_bytes().checked_span(span(10, 30)),
+            Err(SliceError::OutOfBounds {
+                span: span(10, 30),
+                len: 15
+            })
+        );
+        assert_eq!(
+            s.as_bytes().to_vec().checked_span(span(3, 2)),
+            Err(SliceError::Reversed { span: span(3, 2) })
+        );
+        assert_eq!(slice_bytes(s.as_bytes(), span(8, 9)), Ok(&b"\xC3"[..]));
+
+        let err = std::panic::catch_unwind(|| s.resolve(span(0, 20))).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<String>().unwrap(),
+            "span 0..20 is out of bounds of the source, which is 15 bytes long"
+        );
+    }
+
+    #[test]
+    fn slice_of() {
+        let s = String::from("this is é test");
+        assert_eq!(Span::from(8..10).slice_of(&s), "é");
+        assert_eq!(Span::from(8..9).try_slice_of(&s), None);
+        assert_eq!(Span::from(0..4).slice_of(s.as_bytes()), b"this");
+        assert_eq!(Span::from(0..30).try_slice_of(&s.into_bytes()), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_of_out_of_bounds() {
+        Span::from(3..5).slice_of("ab");
+    }
+
+    #[test]
+    fn with_span() {
+        let span = Span::from(1..3);
+        let mut item = WithSpan::new(String::from("ab"), span);
+        assert_eq!(item.len(), 2);
+
+        item.as_mut().map(|s| s.push('c'));
+        assert_eq!(item.as_ref().map(|s| s.len()), WithSpan::new(3, span));
+        assert_eq!(item.map(|s| s + "d").into_parts(), ("abcd".into(), span));
+
+        let item = WithSpan::new('a', span).map_span(|span| ("file", span));
+        assert_eq!(item.as_ref().span, ("file", span));
+    }
+
+    #[test]
+    fn span_range() {
+        let span = Span::from(5..9);
+        assert_eq!(span, Span { start: 5, end: 9 });
+        assert_eq!(core::ops::Range::from(span), 5..9);
+
+        let mut s = String::from("this is a test");
+        assert_eq!(s.drain(span).collect::<String>(), "is a");
+        assert_eq!(s, "this  test");
+    }
+
+    #[test]
+    fn span_ord() {
+        let mut spans = vec![Span::from(3..4), Span::from(1..5), Span::from(1..2)];
+        spans.sort();
+        assert_eq!(
+            spans,
+            vec![Span::from(1..2), Span::from(1..5), Span::from(3..4)]
+        );
+
+        let map = spans
+            .iter()
+            .map(|&span| (WithSpan::new("a", span), span.start))
+            .collect::<std::collections::HashMap<_, _>>();
+        assert_eq!(map[&WithSpan::new("a", Span::from(3..4))], 3);
+    }
+
+    #[test]
+    fn span_display() {
+        let span = Span { start: 5, end: 9 };
+        assert_eq!(span.to_string(), "5..9");
+
+        let index = LineIndex::new("abc\nd efgh ij");
+        assert_eq!(span.display_with(&index).to_string(), "2:2-2:6");
+    }
+}
@@ -0,0 +1,231 @@
+use crate::WithSpan;
+use alloc::vec::Vec;
+
+/// The statistics for a single kind of token, in [`TokenStats`](./struct.TokenStats.html)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct KindStats {
+    /// The number of tokens of this kind
+    pub count: usize,
+    /// The total length of the tokens of this kind, in bytes
+    pub bytes: usize,
+}
+
+/// `TokenStats` collects statistics about spanned tokens
+///
+/// Tokens can be recorded one at a time, collected from an iterator, or
+/// recorded as they pass through an iterator with
+/// [`TokenIteratorExt::record_stats`](./trait.TokenIteratorExt.html#method.record_stats)
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, TokenStats};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Word,
+///     #[regex = "[0-9]+"]
+///     Number,
+/// }
+///
+/// let stats = lex::<Token, _>("abc 12 de ? f").spanned().collect::<TokenStats<_>>();
+/// assert_eq!(stats.total(), 5);
+/// assert_eq!(stats.get(&Token::Word).count, 3);
+/// assert_eq!(stats.get(&Token::Word).bytes, 6);
+/// assert_eq!(stats.error_ratio(), 0.2);
+/// assert_eq!(stats.longest().map(|k| k.item), Some(Token::Word));
+///
+/// let histogram = stats.histogram().map(|(k, s)| (*k, s.count)).collect::<Vec<_>>();
+/// assert_eq!(histogram, vec![(Token::Word, 3), (Token::Number, 1), (Token::Unknown, 1)]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenStats<T> {
+    kinds: Vec<(T, KindStats)>,
+    total: usize,
+    longest: Option<WithSpan<T>>,
+}
+
+impl<T> Default for TokenStats<T> {
+    fn default() -> Self {
+        Self {
+            kinds: vec![],
+            total: 0,
+            longest: None,
+        }
+    }
+}
+
+impl<T> TokenStats<T>
+where
+    T: Clone + PartialEq + ::logos::Logos,
+{
+    /// Create an empty collector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a `token`
+    pub fn record(&mut self, token: &WithSpan<T>) {
+        let len = token.span.end - token.span.start;
+        match self.kinds.iter_mut().find(|(k, _)| *k == token.item) {
+            Some((_, stats)) => {
+                stats.count += 1;
+                stats.bytes += len;
+            }
+            None => self.kinds.push((
+                token.item.clone(),
+                KindStats {
+                    count: 1,
+                    bytes: len,
+                },
+            )),
+        }
+
+        self.total += 1;
+        let longer = match &self.longest {
+            Some(k) => k.span.end - k.span.start < len,
+            None => true,
+        };
+        if longer {
+            self.longest.replace(token.clone());
+        }
+    }
+
+    /// The statistics for `kind`
+    pub fn get(&self, kind: &T) -> KindStats {
+        self.kinds
+            .iter()
+            .find(|(k, _)| k == kind)
+            .map(|&(_, stats)| stats)
+            .unwrap_or_default()
+    }
+
+    /// The fraction of the tokens that were the
+    /// [`#[logos::error]`](https://docs.rs/logos/latest/logos/trait.Logos.html#associatedconstant.ERROR)
+    /// token, or `0.0` if no tokens were recorded
+    pub fn error_ratio(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.get(&T::ERROR).count as f64 / self.total as f64
+    }
+}
+
+impl<T> TokenStats<T> {
+    /// The number of tokens recorded
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// The first of the longest tokens recorded
+    pub fn longest(&self) -> Option<WithSpan<T>>
+    where
+        T: Clone,
+    {
+        self.longest.clone()
+    }
+
+    /// Iterate over the kinds and their statistics, in the order they were first seen
+    pub fn iter(&self) -> impl Iterator<Item = (&T, KindStats)> + '_ {
+        self.kinds.iter().map(|(k, stats)| (k, *stats))
+    }
+
+    /// Iterate over the kinds and their statistics, from the most to the least common
+    ///
+    /// Kinds with the same count are in the order they were first seen
+    pub fn histogram(&self) -> impl Iterator<Item = (&T, KindStats)> + '_ {
+        let mut kinds = self.iter().collect::<Vec<_>>();
+        kinds.sort_by_key(|(_, stats)| core::cmp::Reverse(stats.count));
+        kinds.into_iter()
+    }
+}
+
+impl<T> Extend<WithSpan<T>> for TokenStats<T>
+where
+    T: Clone + PartialEq + ::logos::Logos,
+{
+    fn extend<I: IntoIterator<Item = WithSpan<T>>>(&mut self, iter: I) {
+        for token in iter {
+            self.record(&token);
+        }
+    }
+}
+
+impl<T> core::iter::FromIterator<WithSpan<T>> for TokenStats<T>
+where
+    T: Clone + PartialEq + ::logos::Logos,
+{
+    fn from_iter<I: IntoIterator<Item = WithSpan<T>>>(iter: I) -> Self {
+        let mut stats = Self::default();
+        stats.extend(iter);
+        stats
+    }
+}
+
+/// An iterator that records [`TokenStats`](./struct.TokenStats.html) of the tokens passing through it
+///
+/// This is created with [`TokenIteratorExt::record_stats`](./trait.TokenIteratorExt.html#method.record_stats)
+pub struct RecordStats<'a, I, T> {
+    pub(crate) iter: I,
+    pub(crate) stats: &'a mut TokenStats<T>,
+}
+
+impl<I, T> Iterator for RecordStats<'_, I, T>
+where
+    I: Iterator<Item = WithSpan<T>>,
+    T: Clone + PartialEq + ::logos::Logos,
+{
+    type Item = WithSpan<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.iter.next()?;
+        self.stats.record(&token);
+        Some(token)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TokenIteratorExt as _;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+    }
+
+    #[test]
+    fn record_stats() {
+        let mut stats = TokenStats::new();
+        assert_eq!(stats.error_ratio(), 0.0);
+        assert!(stats.longest().is_none());
+
+        let words = crate::lex::<Token, _>("a bcd ? efg")
+            .spanned()
+            .record_stats(&mut stats)
+            .filter(|k| k.item == Token::Word)
+            .count();
+        assert_eq!(words, 3);
+
+        assert_eq!(stats.total(), 4);
+        assert_eq!(stats.get(&Token::Word), KindStats { count: 3, bytes: 7 });
+        assert_eq!(stats.get(&Token::Eof), KindStats::default());
+        assert_eq!(stats.error_ratio(), 0.25);
+        assert_eq!(
+            stats.longest().map(|k| k.span),
+            Some(crate::Span::from(2..5))
+        );
+    }
+}
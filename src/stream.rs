@@ -0,0 +1,442 @@
+use crate::{Span, WithSpan};
+use alloc::vec::Vec;
+use core::ops::{Range, RangeInclusive};
+
+/// `TokenId` is a stable handle to a token in a [`TokenStream`](./struct.TokenStream.html)
+///
+/// Unlike an index, an id keeps referring to the same token after tokens before
+/// it are [replaced](./struct.TokenStream.html#method.replace). Ids are only
+/// meaningful for the stream that they came from
+///
+/// ```rust
+/// # use logos_iterator::{Span, TokenStream, WithSpan};
+/// let mut stream = "abc"
+///     .char_indices()
+///     .map(|(i, c)| WithSpan::new(c, Span::from(i..i + 1)))
+///     .collect::<TokenStream<_>>();
+///
+/// let c = stream.id_at(2).unwrap();
+/// stream.replace(0..1, vec![WithSpan::new('x', Span::from(0..1)), WithSpan::new('y', Span::from(0..1))]);
+///
+/// // the index of `c` changed, but its id didn't
+/// assert_eq!(stream.token(c).map(|k| k.item), Some('c'));
+/// assert_eq!(stream.index_of(c), Some(3));
+///
+/// let y = stream.id_at(1).unwrap();
+/// let items = stream.slice(y..=c).unwrap().iter().map(|k| k.item).collect::<String>();
+/// assert_eq!(items, "ybc");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TokenId(usize);
+
+/// `TokenStream` is a collected stream of spanned tokens, in source order
+///
+/// This is created by collecting a spanned lexer
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, TokenStream};
+/// # #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// # enum Token {
+/// #     #[end]
+/// #     Eof,
+/// #     #[error]
+/// #     Unknown,
+/// #     #[regex = "[a-z]+"]
+/// #     Word,
+/// # }
+/// let stream = lex::<Token, _>("abc def").spanned().collect::<TokenStream<_>>();
+/// assert_eq!(stream.len(), 2);
+/// ```
+#[derive(Clone)]
+pub struct TokenStream<T> {
+    tokens: Vec<WithSpan<T>>,
+    // `None` until the stream is edited, while the ids are the indices
+    ids: Option<Ids>,
+}
+
+#[derive(Clone)]
+struct Ids {
+    // the id of each token
+    ids: Vec<TokenId>,
+    // the index of the token with each id, if it is still in the stream
+    indices: Vec<Option<usize>>,
+}
+
+impl<T> Default for TokenStream<T> {
+    fn default() -> Self {
+        Self::new(vec![])
+    }
+}
+
+impl<T> PartialEq for TokenStream<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.tokens == other.tokens
+    }
+}
+
+impl<T> core::fmt::Debug for TokenStream<T>
+where
+    T: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TokenStream")
+            .field("tokens", &self.tokens)
+            .finish()
+    }
+}
+
+impl<T> TokenStream<T> {
+    /// Create a stream from `tokens`, which should be in source order
+    pub fn new(tokens: Vec<WithSpan<T>>) -> Self {
+        Self { tokens, ids: None }
+    }
+
+    /// Consume the stream, returning the tokens
+    pub fn into_inner(self) -> Vec<WithSpan<T>> {
+        self.tokens
+    }
+
+    /// Find the token that contains the byte `offset`, along with its index
+    ///
+    /// This is a binary search over the spans
+    pub fn token_at(&self, offset: usize) -> Option<(usize, &WithSpan<T>)> {
+        let index = self
+            .tokens
+            .partition_point(|k| k.span.start <= offset)
+            .checked_sub(1)?;
+        let token = &self.tokens[index];
+        if offset < token.span.end {
+            Some((index, token))
+        } else {
+            None
+        }
+    }
+
+    /// Find the last token that ends at or before the byte `offset`, along with its index
+    pub fn token_before(&self, offset: usize) -> Option<(usize, &WithSpan<T>)> {
+        let index = self
+            .tokens
+            .partition_point(|k| k.span.end <= offset)
+            .checked_sub(1)?;
+        Some((index, &self.tokens[index]))
+    }
+
+    /// Find the first token that starts at or after the byte `offset`, along with its index
+    pub fn token_after(&self, offset: usize) -> Option<(usize, &WithSpan<T>)> {
+        let index = self.tokens.partition_point(|k| k.span.start < offset);
+        self.tokens.get(index).map(|token| (index, token))
+    }
+
+    /// Get the contiguous tokens whose spans are entirely within `span`
+    pub fn in_span(&self, span: Span) -> &[WithSpan<T>] {
+        let start = self.tokens.partition_point(|k| k.span.start < span.start);
+        let len = self.tokens[start..].partition_point(|k| k.span.end <= span.end);
+        &self.tokens[start..start + len]
+    }
+}
+
+impl<T> TokenStream<T> {
+    /// The id of the token at `index`
+    pub fn id_at(&self, index: usize) -> Option<TokenId> {
+        if index >= self.tokens.len() {
+            return None;
+        }
+        match &self.ids {
+            Some(ids) => Some(ids.ids[index]),
+            None => Some(TokenId(index)),
+        }
+    }
+
+    /// The index of the token with the `id`, if it is still in the stream
+    pub fn index_of(&self, id: TokenId) -> Option<usize> {
+        match &self.ids {
+            Some(ids) => ids.indices.get(id.0).copied().flatten(),
+            None => Some(id.0).filter(|&index| index < self.tokens.len()),
+        }
+    }
+
+    /// The token with the `id`, if it is still in the stream
+    pub fn token(&self, id: TokenId) -> Option<&WithSpan<T>> {
+        self.index_of(id).map(|index| &self.tokens[index])
+    }
+
+    /// The span of the token with the `id`, if it is still in the stream
+    pub fn span(&self, id: TokenId) -> Option<Span> {
+        self.token(id).map(|token| token.span)
+    }
+
+    /// The tokens from the token with the id `ids.start()` to the one with the
+    /// id `ids.end()`, inclusive
+    ///
+    /// This returns `None` if either token isn't in the stream, or if the first
+    /// one is after the last one
+    pub fn slice(&self, ids: RangeInclusive<TokenId>) -> Option<&[WithSpan<T>]> {
+        let start = self.index_of(*ids.start())?;
+        let end = self.index_of(*ids.end())?;
+        if start > end {
+            return None;
+        }
+        Some(&self.tokens[start..=end])
+    }
+
+    /// Replace the tokens at the indices `range` with `tokens`, returning the
+    /// tokens that were removed
+    ///
+    /// The new tokens get new ids, and the other tokens keep theirs
+    ///
+    /// # Panics
+    /// This panics if `range` is out of bounds
+    pub fn replace(
+        &mut self,
+        range: Range<usize>,
+        tokens: impl IntoIterator<Item = WithSpan<T>>,
+    ) -> Vec<WithSpan<T>> {
+        let len = self.tokens.len();
+        let ids = self.ids.get_or_insert_with(|| Ids {
+            ids: (0..len).map(TokenId).collect(),
+            indices: (0..len).map(Some).collect(),
+        });
+
+        let start = range.start;
+        let removed = self
+            .tokens
+            .splice(range.clone(), tokens)
+            .collect::<Vec<_>>();
+        let added = self.tokens.len() + removed.len() - len;
+
+        for id in &ids.ids[range.clone()] {
+            ids.indices[id.0] = None;
+        }
+        let next = ids.indices.len();
+        ids.indices.extend((0..added).map(|i| Some(start + i)));
+        ids.ids.splice(range, (next..next + added).map(TokenId));
+        for (index, id) in ids.ids.iter().enumerate().skip(start + added) {
+            ids.indices[id.0] = Some(index);
+        }
+        removed
+    }
+}
+
+impl<T> TokenStream<T> {
+    /// Get the `n` tokens before and after the token at `index`
+    ///
+    /// # Panics
+    /// This panics if `index` is out of bounds
+    pub fn context(&self, index: usize, n: usize) -> TokenContext<'_, T> {
+        self.window(index..index + 1, n, self.tokens[index].span)
+    }
+
+    /// Get the `n` tokens before and after the tokens that overlap with `span`
+    pub fn context_around(&self, span: Span, n: usize) -> TokenContext<'_, T> {
+        let start = self.tokens.partition_point(|k| k.span.end <= span.start);
+        let len = self.tokens[start..].partition_point(|k| k.span.start < span.end);
+        self.window(start..start + len, n, span)
+    }
+
+    fn window(&self, range: core::ops::Range<usize>, n: usize, span: Span) -> TokenContext<'_, T> {
+        let before = &self.tokens[range.start.saturating_sub(n)..range.start];
+        let after = &self.tokens[range.end..(range.end + n).min(self.tokens.len())];
+        let tokens = &self.tokens[range];
+
+        let span = before
+            .iter()
+            .chain(tokens)
+            .chain(after)
+            .fold(span, |span, k| span.join(k.span));
+
+        TokenContext {
+            before,
+            tokens,
+            after,
+            span,
+        }
+    }
+}
+
+/// `TokenContext` is a window of tokens around some tokens in a
+/// [`TokenStream`](./struct.TokenStream.html)
+///
+/// This is created with [`TokenStream::context`](./struct.TokenStream.html#method.context)
+/// or [`TokenStream::context_around`](./struct.TokenStream.html#method.context_around)
+#[derive(Debug, PartialEq)]
+pub struct TokenContext<'a, T> {
+    /// The tokens before
+    pub before: &'a [WithSpan<T>],
+    /// The tokens that the context is around
+    pub tokens: &'a [WithSpan<T>],
+    /// The tokens after
+    pub after: &'a [WithSpan<T>],
+    /// The span covering all of the tokens
+    pub span: Span,
+}
+
+impl<T> Clone for TokenContext<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TokenContext<'_, T> {}
+
+impl<T> core::ops::Deref for TokenStream<T> {
+    type Target = [WithSpan<T>];
+    fn deref(&self) -> &Self::Target {
+        &self.tokens
+    }
+}
+
+impl<T> From<Vec<WithSpan<T>>> for TokenStream<T> {
+    fn from(tokens: Vec<WithSpan<T>>) -> Self {
+        Self::new(tokens)
+    }
+}
+
+impl<T> core::iter::FromIterator<WithSpan<T>> for TokenStream<T> {
+    fn from_iter<I: IntoIterator<Item = WithSpan<T>>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl<T> IntoIterator for TokenStream<T> {
+    type Item = WithSpan<T>;
+    type IntoIter = alloc::vec::IntoIter<WithSpan<T>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.tokens.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TokenStream<T> {
+    type Item = &'a WithSpan<T>;
+    type IntoIter = core::slice::Iter<'a, WithSpan<T>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.tokens.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+        #[regex = "[0-9]+"]
+        Number,
+    }
+
+    #[test]
+    fn token_at() {
+        let stream = crate::lex::<Token, _>("abc  12 d")
+            .spanned()
+            .collect::<TokenStream<_>>();
+
+        let at = |offset| stream.token_at(offset).map(|(i, k)| (i, k.item));
+        assert_eq!(at(0), Some((0, Token::Word)));
+        assert_eq!(at(2), Some((0, Token::Word)));
+        assert_eq!(at(3), None);
+        assert_eq!(at(5), Some((1, Token::Number)));
+        assert_eq!(at(8), Some((2, Token::Word)));
+        assert_eq!(at(9), None);
+    }
+
+    #[test]
+    fn token_before_after() {
+        let stream = crate::lex::<Token, _>("abc  12 d")
+            .spanned()
+            .collect::<TokenStream<_>>();
+
+        let before = |offset| stream.token_before(offset).map(|(i, _)| i);
+        let after = |offset| stream.token_after(offset).map(|(i, _)| i);
+        assert_eq!((before(0), after(0)), (None, Some(0)));
+        assert_eq!((before(1), after(1)), (None, Some(1)));
+        assert_eq!((before(4), after(4)), (Some(0), Some(1)));
+        assert_eq!((before(7), after(7)), (Some(1), Some(2)));
+        assert_eq!((before(9), after(9)), (Some(2), None));
+    }
+
+    #[test]
+    fn context() {
+        let stream = crate::lex::<Token, _>("a 1 b 2 c 3")
+            .spanned()
+            .collect::<TokenStream<_>>();
+
+        let context = stream.context(2, 1);
+        assert_eq!((context.before.len(), context.after.len()), (1, 1));
+        assert_eq!(context.tokens[0].span, Span::from(4..5));
+        assert_eq!(context.span, Span::from(2..7));
+
+        let context = stream.context(0, 2);
+        assert_eq!((context.before.len(), context.after.len()), (0, 2));
+        assert_eq!(context.span, Span::from(0..5));
+
+        let context = stream.context_around(Span::from(3..9), 1);
+        assert_eq!(context.tokens.len(), 3);
+        assert_eq!(context.span, Span::from(2..11));
+
+        let context = stream.context_around(Span::from(5..6), 2);
+        assert!(context.tokens.is_empty());
+        assert_eq!(context.before.len() + context.after.len(), 4);
+        assert_eq!(context.span, Span::from(2..9));
+    }
+
+    #[test]
+    fn ids() {
+        let mut stream = crate::lex::<Token, _>("a 1 b 2")
+            .spanned()
+            .collect::<TokenStream<_>>();
+        let ids = (0..4).map(|i| stream.id_at(i).unwrap()).collect::<Vec<_>>();
+        assert_eq!(stream.id_at(4), None);
+        assert_eq!(stream.span(ids[1]), Some(Span::from(2..3)));
+
+        let removed = stream.replace(1..3, vec![WithSpan::new(Token::Word, Span::from(2..5))]);
+        assert_eq!(removed.len(), 2);
+        assert_eq!((stream.token(ids[1]), stream.token(ids[2])), (None, None));
+        assert_eq!(stream.index_of(ids[3]), Some(2));
+        assert_eq!(stream.span(ids[3]), Some(Span::from(6..7)));
+
+        let new = stream.id_at(1).unwrap();
+        assert!(!ids.contains(&new));
+        assert_eq!(stream.slice(ids[0]..=ids[3]).map(<[_]>::len), Some(3));
+        assert_eq!(
+            stream.slice(new..=new).map(|k| k[0].span),
+            Some(Span::from(2..5))
+        );
+        assert_eq!(stream.slice(ids[3]..=ids[0]), None);
+        assert_eq!(stream.slice(ids[0]..=ids[1]), None);
+
+        stream.replace(0..0, vec![WithSpan::new(Token::Number, Span::from(0..0))]);
+        assert_eq!(stream.index_of(ids[0]), Some(1));
+        assert_eq!(stream.index_of(new), Some(2));
+        assert_eq!(stream.len(), 4);
+    }
+
+    #[test]
+    fn in_span() {
+        let stream = crate::lex::<Token, _>("abc  12 d 3")
+            .spanned()
+            .collect::<TokenStream<_>>();
+
+        let items = |span| {
+            stream
+                .in_span(span)
+                .iter()
+                .map(|k| k.item)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(items(Span::from(0..11)).len(), 4);
+        assert_eq!(items(Span::from(1..9)), vec![Token::Number, Token::Word]);
+        assert_eq!(items(Span::from(5..8)), vec![Token::Number]);
+        assert!(items(Span::from(5..6)).is_empty());
+        assert!(items(Span::from(20..30)).is_empty());
+    }
+}
@@ -0,0 +1,344 @@
+//! Helpers for testing token streams
+#[cfg(feature = "std")]
+pub mod corpus;
+
+use crate::{LineIndex, Span, TokenStream, TriviaStream, WithSpan};
+use alloc::{string::String, vec::Vec};
+
+/// `SpanIgnoringEq` wraps a [`WithSpan`](../struct.WithSpan.html) so that it is
+/// compared only by its item
+///
+/// ```rust
+/// # use logos_iterator::{Span, WithSpan, testing::SpanIgnoringEq};
+/// let left = SpanIgnoringEq(WithSpan::new('a', Span::from(0..1)));
+/// let right = SpanIgnoringEq(WithSpan::new('a', Span::from(3..4)));
+/// assert_eq!(left, right);
+/// ```
+#[derive(Copy, Clone)]
+pub struct SpanIgnoringEq<T, S = Span>(pub WithSpan<T, S>);
+
+impl<T, S> PartialEq for SpanIgnoringEq<T, S>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0.item == other.0.item
+    }
+}
+
+impl<T, S> core::fmt::Debug for SpanIgnoringEq<T, S>
+where
+    T: core::fmt::Debug,
+    S: core::fmt::Display,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?} at {}", self.0.item, self.0.span)
+    }
+}
+
+/// `KindMismatch` is the first place where two token streams have different items
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct KindMismatch<'a, T, S = Span> {
+    /// The index of the mismatch
+    pub index: usize,
+    /// The token in the left stream, if it wasn't shorter
+    pub left: Option<&'a WithSpan<T, S>>,
+    /// The token in the right stream, if it wasn't shorter
+    pub right: Option<&'a WithSpan<T, S>>,
+}
+
+impl<T, S> core::fmt::Display for KindMismatch<'_, T, S>
+where
+    T: core::fmt::Debug,
+    S: core::fmt::Display,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fn side<T, S>(token: Option<&WithSpan<T, S>>) -> String
+        where
+            T: core::fmt::Debug,
+            S: core::fmt::Display,
+        {
+            match token {
+                Some(token) => format!("{:?} at {}", token.item, token.span),
+                None => "missing".into(),
+            }
+        }
+
+        write!(
+            f,
+            "token streams differ at index {}\n  left: {}\n right: {}",
+            self.index,
+            side(self.left),
+            side(self.right)
+        )
+    }
+}
+
+/// Find the first index where the items of `left` and `right` differ, ignoring the spans
+pub fn first_kind_mismatch<'a, T, S>(
+    left: &'a [WithSpan<T, S>],
+    right: &'a [WithSpan<T, S>],
+) -> Option<KindMismatch<'a, T, S>>
+where
+    T: PartialEq,
+{
+    (0..left.len().max(right.len()))
+        .map(|index| KindMismatch {
+            index,
+            left: left.get(index),
+            right: right.get(index),
+        })
+        .find(|m| match (m.left, m.right) {
+            (Some(left), Some(right)) => left.item != right.item,
+            _ => true,
+        })
+}
+
+/// Assert that the items of `left` and `right` are equal, ignoring the spans
+///
+/// # Panics
+/// This panics with the first [`KindMismatch`](./struct.KindMismatch.html) if they differ
+#[track_caller]
+pub fn assert_kinds_eq<T, S>(left: &[WithSpan<T, S>], right: &[WithSpan<T, S>])
+where
+    T: PartialEq + core::fmt::Debug,
+    S: core::fmt::Display,
+{
+    if let Some(mismatch) = first_kind_mismatch(left, right) {
+        panic!("{}", mismatch)
+    }
+}
+
+/// Assert that [printing](../struct.TriviaStream.html#method.print) the `stream` reproduces `source`
+///
+/// # Panics
+/// This panics with the position of the first difference, if they differ
+#[track_caller]
+pub fn assert_round_trip<T>(source: &str, stream: &TriviaStream<T>) {
+    let printed = stream.print(source);
+    if printed == source {
+        return;
+    }
+
+    let offset = source
+        .char_indices()
+        .zip(printed.chars())
+        .find(|((_, left), right)| left != right)
+        .map_or(source.len().min(printed.len()), |((i, _), _)| i);
+    let pos = LineIndex::new(source).line_col(offset);
+    let context = |s: &str| {
+        s.get(offset..)
+            .unwrap_or("")
+            .chars()
+            .take(20)
+            .collect::<String>()
+    };
+
+    panic!(
+        "source doesn't round-trip, first difference at {}:{}\n  expected: {:?}\n     found: {:?}",
+        pos.line,
+        pos.column,
+        context(source),
+        context(&printed)
+    )
+}
+
+/// `FixtureError` is why a fixture couldn't be parsed by [`fixture`](./fn.fixture.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FixtureError {
+    /// The entry at the byte `offset` of the fixture isn't a `Kind@start..end`
+    Malformed { offset: usize },
+    /// The kind of the entry at the byte `offset` of the fixture isn't known
+    UnknownKind { offset: usize },
+}
+
+impl core::fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Malformed { offset } => {
+                write!(f, "expected `Kind@start..end` at offset {}", offset)
+            }
+            Self::UnknownKind { offset } => write!(f, "unknown token kind at offset {}", offset),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FixtureError {}
+
+/// Build a token stream from a `fixture` of whitespace separated `Kind@start..end`
+/// entries, with `kind` looking up each kind by its name
+///
+/// This lets a parser be tested without running a lexer, or writing out the spans
+/// by hand. See [`fixture_from_slices`](./fn.fixture_from_slices.html) to make
+/// the spans from the text of the tokens instead
+///
+/// ```rust
+/// # use logos_iterator::{Span, WithSpan, testing::{fixture, FixtureError}};
+/// #[derive(PartialEq, Debug)]
+/// enum Token {
+///     Digit,
+///     Plus,
+/// }
+///
+/// let kind = |name: &str| match name {
+///     "Digit" => Some(Token::Digit),
+///     "Plus" => Some(Token::Plus),
+///     _ => None,
+/// };
+///
+/// let stream = fixture("Digit@0..1 Plus@2..3 Digit@4..5", kind).unwrap();
+/// assert_eq!(stream.len(), 3);
+/// assert_eq!(stream[1], WithSpan::new(Token::Plus, Span::from(2..3)));
+///
+/// assert_eq!(fixture("Digit@0..1 Minus@2..3", kind), Err(FixtureError::UnknownKind { offset: 11 }));
+/// ```
+pub fn fixture<T>(
+    fixture: &str,
+    mut kind: impl FnMut(&str) -> Option<T>,
+) -> Result<TokenStream<T>, FixtureError> {
+    let mut tokens = Vec::new();
+    let mut rest = fixture;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return Ok(tokens.into());
+        }
+        let offset = fixture.len() - rest.len();
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (entry, tail) = rest.split_at(end);
+        rest = tail;
+
+        let malformed = FixtureError::Malformed { offset };
+        let (name, span) = entry.split_once('@').ok_or(malformed)?;
+        let (start, end) = span.split_once("..").ok_or(malformed)?;
+        let (start, end) = match (start.parse(), end.parse()) {
+            (Ok(start), Ok(end)) if start <= end => (start, end),
+            _ => return Err(malformed),
+        };
+        let item = kind(name).ok_or(FixtureError::UnknownKind { offset })?;
+        tokens.push(WithSpan::new(item, Span { start, end }));
+    }
+}
+
+/// Build a source and its token stream from `pieces` of a token and its text,
+/// with `separator` between the texts
+///
+/// ```rust
+/// # use logos_iterator::{Span, testing::fixture_from_slices};
+/// let (source, stream) = fixture_from_slices(" ", vec![('1', "12"), ('+', "+"), ('3', "3")]);
+/// assert_eq!(source, "12 + 3");
+/// assert_eq!(stream[2].span, Span::from(5..6));
+/// ```
+pub fn fixture_from_slices<'a, T>(
+    separator: &str,
+    pieces: impl IntoIterator<Item = (T, &'a str)>,
+) -> (String, TokenStream<T>) {
+    let mut source = String::new();
+    let mut tokens = Vec::new();
+    for (i, (item, text)) in pieces.into_iter().enumerate() {
+        if i > 0 {
+            source.push_str(separator);
+        }
+        let start = source.len();
+        source.push_str(text);
+        tokens.push(WithSpan::new(item, Span::from(start..source.len())));
+    }
+    (source, tokens.into())
+}
+
+/// Lex each `char` of `source` as a token, for the tests
+#[cfg(test)]
+pub(crate) fn chars(source: &str) -> Vec<WithSpan<char>> {
+    source
+        .char_indices()
+        .map(|(i, c)| WithSpan::new(c, Span::from(i..i + c.len_utf8())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_mismatch() {
+        let left = vec![
+            WithSpan::new('a', Span::from(0..1)),
+            WithSpan::new('b', Span::from(1..2)),
+        ];
+        let mut right = vec![
+            WithSpan::new('a', Span::from(4..5)),
+            WithSpan::new('b', Span::from(5..6)),
+        ];
+        assert_kinds_eq(&left, &right);
+
+        right[1].item = 'c';
+        assert_eq!(
+            first_kind_mismatch(&left, &right).unwrap().to_string(),
+            "token streams differ at index 1\n  left: 'b' at 1..2\n right: 'c' at 5..6"
+        );
+
+        right.pop();
+        let mismatch = first_kind_mismatch(&left, &right).unwrap();
+        assert_eq!((mismatch.index, mismatch.right), (1, None));
+        assert!(first_kind_mismatch(&left[..1], &right).is_none());
+    }
+
+    #[test]
+    fn round_trip() {
+        let source = "ab\ncd";
+        let pieces = |indices: &[usize]| {
+            let tokens = indices
+                .iter()
+                .map(|&i| WithSpan::new((), Span::from(i..i + 1)));
+            TriviaStream::attach(source, tokens, |_| false)
+        };
+        assert_round_trip(source, &pieces(&[0, 1, 2, 3, 4]));
+        assert_round_trip(source, &pieces(&[1, 3]));
+
+        let err = std::panic::catch_unwind(|| assert_round_trip(source, &pieces(&[0, 1, 4, 3])))
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<String>().unwrap(),
+            "source doesn't round-trip, first difference at 2:3\n  expected: \"\"\n     found: \"c\""
+        );
+    }
+
+    #[test]
+    fn fixtures() {
+        let kind = |name: &str| name.parse::<char>().ok();
+        let stream = fixture("  a@0..1\nb@2..4 ", kind).unwrap();
+        assert_eq!(
+            stream.iter().cloned().collect::<Vec<_>>(),
+            vec![
+                WithSpan::new('a', Span::from(0..1)),
+                WithSpan::new('b', Span::from(2..4))
+            ]
+        );
+        assert_eq!(fixture("", kind).map(|s| s.len()), Ok(0));
+
+        for (bad, offset) in &[("a", 0), ("a@1", 0), ("a@0..1 b@x..2", 7), ("a@2..1", 0)] {
+            assert_eq!(
+                fixture(bad, kind),
+                Err(FixtureError::Malformed { offset: *offset })
+            );
+        }
+        assert_eq!(
+            fixture("a@0..1 ab@1..2", kind),
+            Err(FixtureError::UnknownKind { offset: 7 })
+        );
+
+        let (source, stream) = fixture_from_slices("", vec![("x", "ab"), ("y", ""), ("z", "c")]);
+        assert_eq!(source, "abc");
+        let spans = stream.iter().map(|k| k.span).collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![Span::from(0..2), Span::from(2..2), Span::from(2..3)]
+        );
+        let spans = chars("aé!").iter().map(|k| k.span).collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![Span::from(0..1), Span::from(1..3), Span::from(3..4)]
+        );
+    }
+}
@@ -0,0 +1,309 @@
+//! Golden-file tests for a corpus of inputs
+//!
+//! A [`Corpus`](./struct.Corpus.html) is a directory of input files. Each input
+//! is lexed, and its tokens are rendered one per line as `Kind start..end "text"`
+//! and compared with the expectation file next to it, which has `.tokens`
+//! appended to its name (`a.txt` is expected to lex to `a.txt.tokens`)
+//!
+//! With [update mode](./struct.Corpus.html#method.with_update), which is on when the
+//! `UPDATE_CORPUS` environment variable is set, the expectation files are written
+//! instead of compared, so they can be reviewed as a diff
+//!
+//! ```rust
+//! # use logos::Logos;
+//! # use logos_iterator::{lex, testing::corpus::Corpus};
+//! #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+//! enum Token {
+//!     #[end]
+//!     Eof,
+//!     #[error]
+//!     Unknown,
+//!     #[regex = "[a-z]+"]
+//!     Word,
+//! }
+//!
+//! let dir = std::env::temp_dir().join("logos_iterator_corpus_doc");
+//! std::fs::create_dir_all(&dir).unwrap();
+//! std::fs::write(dir.join("a.txt"), "hello world").unwrap();
+//! std::fs::write(dir.join("a.txt.tokens"), "Word 0..5 \"hello\"\nWord 6..11 \"world\"\n").unwrap();
+//!
+//! let report = Corpus::new(&dir)
+//!     .with_update(false)
+//!     .run(|source| lex::<Token, _>(source).spanned().collect())
+//!     .unwrap();
+//! assert_eq!(report.checked, 1);
+//! assert!(report.is_ok(), "{}", report);
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+use crate::WithSpan;
+use std::{
+    fmt::Write as _,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// The environment variable that turns on update mode
+pub const UPDATE_VAR: &str = "UPDATE_CORPUS";
+
+/// `Corpus` is a directory of inputs with their expected tokens
+#[derive(Clone, Debug)]
+pub struct Corpus {
+    dir: PathBuf,
+    extension: Option<String>,
+    update: bool,
+}
+
+impl Corpus {
+    /// Create a corpus of the files in `dir` and its subdirectories
+    ///
+    /// Update mode is on if the `UPDATE_CORPUS` environment variable is set to
+    /// something other than `0`
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let update = std::env::var_os(UPDATE_VAR).is_some_and(|s| !s.is_empty() && s != "0");
+        Self {
+            dir: dir.into(),
+            extension: None,
+            update,
+        }
+    }
+
+    /// Only use the files with the `extension` as inputs
+    pub fn with_extension(mut self, extension: impl Into<String>) -> Self {
+        self.extension = Some(extension.into());
+        self
+    }
+
+    /// Write the expectation files instead of comparing them, if `update` is `true`
+    pub fn with_update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Lex each input with `lex`, and compare (or update) its expectation file
+    ///
+    /// The inputs are visited in order of their paths. This returns an error if
+    /// the directory can't be walked, or a file can't be read or written
+    pub fn run<T>(&self, mut lex: impl FnMut(&str) -> Vec<WithSpan<T>>) -> io::Result<CorpusReport>
+    where
+        T: core::fmt::Debug,
+    {
+        let mut inputs = vec![];
+        self.walk(&self.dir, &mut inputs)?;
+        inputs.sort();
+
+        let mut report = CorpusReport::default();
+        for input in inputs {
+            let source = std::fs::read_to_string(&input)?;
+            let actual = render(&source, &lex(&source));
+            let path = expectation_path(&input);
+            report.checked += 1;
+
+            if self.update {
+                if std::fs::read_to_string(&path).ok().as_deref() != Some(&*actual) {
+                    std::fs::write(&path, &actual)?;
+                    report.updated.push(path);
+                }
+                continue;
+            }
+
+            let expected = match std::fs::read_to_string(&path) {
+                Ok(expected) => Some(expected),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+                Err(err) => return Err(err),
+            };
+            if expected.as_deref() != Some(&*actual) {
+                report.mismatches.push(Mismatch {
+                    input,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(report)
+    }
+
+    /// Run the corpus like [`run`](#method.run)
+    ///
+    /// # Panics
+    /// This panics with all of the mismatches, if there are any, or if the
+    /// corpus couldn't be run
+    #[track_caller]
+    pub fn assert<T>(&self, lex: impl FnMut(&str) -> Vec<WithSpan<T>>)
+    where
+        T: core::fmt::Debug,
+    {
+        match self.run(lex) {
+            Ok(report) if report.is_ok() => {}
+            Ok(report) => panic!("{}", report),
+            Err(err) => panic!("cannot run the corpus at {}: {}", self.dir.display(), err),
+        }
+    }
+
+    fn walk(&self, dir: &Path, inputs: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                self.walk(&path, inputs)?;
+                continue;
+            }
+            let extension = path.extension().and_then(|s| s.to_str());
+            let wanted = match &self.extension {
+                Some(wanted) => extension == Some(wanted.as_str()),
+                None => extension != Some("tokens"),
+            };
+            if wanted {
+                inputs.push(path);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn expectation_path(input: &Path) -> PathBuf {
+    let mut path = input.as_os_str().to_owned();
+    path.push(".tokens");
+    path.into()
+}
+
+fn render<T>(source: &str, tokens: &[WithSpan<T>]) -> String
+where
+    T: core::fmt::Debug,
+{
+    let mut out = String::new();
+    for token in tokens {
+        let text = source.get(token.span.start..token.span.end).unwrap_or("");
+        let _ = writeln!(out, "{:?} {} {:?}", token.item, token.span, text);
+    }
+    out
+}
+
+/// `Mismatch` is an input whose tokens didn't match its expectation file
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mismatch {
+    /// The path of the input
+    pub input: PathBuf,
+    /// The contents of the expectation file, if there was one
+    pub expected: Option<String>,
+    /// The rendered tokens of the input
+    pub actual: String,
+}
+
+impl core::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let expected = match &self.expected {
+            Some(expected) => expected,
+            None => return write!(f, "{}: missing the expectation file", self.input.display()),
+        };
+
+        let mut expected_lines = expected.lines();
+        let mut actual_lines = self.actual.lines();
+        for line in 1.. {
+            match (expected_lines.next(), actual_lines.next()) {
+                (Some(left), Some(right)) if left == right => continue,
+                (None, None) => break,
+                (left, right) => {
+                    return write!(
+                        f,
+                        "{}: tokens differ at line {}\n  expected: {}\n     found: {}",
+                        self.input.display(),
+                        line,
+                        left.unwrap_or("nothing"),
+                        right.unwrap_or("nothing")
+                    )
+                }
+            }
+        }
+        // only the trailing newlines differ
+        write!(f, "{}: tokens differ at the end", self.input.display())
+    }
+}
+
+/// `CorpusReport` is the outcome of [`Corpus::run`](./struct.Corpus.html#method.run)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CorpusReport {
+    /// The number of inputs that were lexed
+    pub checked: usize,
+    /// The expectation files that were written, in update mode
+    pub updated: Vec<PathBuf>,
+    /// The inputs that didn't match their expectation file
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl CorpusReport {
+    /// Whether every input matched
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl core::fmt::Display for CorpusReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} of {} inputs didn't match",
+            self.mismatches.len(),
+            self.checked
+        )?;
+        for mismatch in &self.mismatches {
+            write!(f, "\n{}", mismatch)?;
+        }
+        if !self.mismatches.is_empty() {
+            write!(
+                f,
+                "\n(set {}=1 to update the expectation files)",
+                UPDATE_VAR
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::chars;
+
+    #[test]
+    fn corpus() {
+        let dir =
+            std::env::temp_dir().join(format!("logos_iterator_corpus_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("a.txt"), "ab").unwrap();
+        std::fs::write(dir.join("nested/b.txt"), "c").unwrap();
+        std::fs::write(dir.join("skipped.md"), "d").unwrap();
+
+        let corpus = Corpus::new(&dir).with_extension("txt");
+        let report = corpus.clone().with_update(false).run(chars).unwrap();
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.mismatches.len(), 2);
+        assert!(report.mismatches.iter().all(|m| m.expected.is_none()));
+
+        let report = corpus.clone().with_update(true).run(chars).unwrap();
+        assert_eq!(
+            report.updated,
+            vec![dir.join("a.txt.tokens"), dir.join("nested/b.txt.tokens")]
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("a.txt.tokens")).unwrap(),
+            "'a' 0..1 \"a\"\n'b' 1..2 \"b\"\n"
+        );
+
+        let corpus = corpus.with_update(false);
+        corpus.assert(chars);
+
+        std::fs::write(dir.join("a.txt"), "ax").unwrap();
+        let report = corpus.run(chars).unwrap();
+        assert_eq!(
+            report.to_string(),
+            format!(
+                "1 of 2 inputs didn't match\n{}: tokens differ at line 2\n  \
+                 expected: 'b' 1..2 \"b\"\n     found: 'x' 1..2 \"x\"\n\
+                 (set UPDATE_CORPUS=1 to update the expectation files)",
+                dir.join("a.txt").display()
+            )
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
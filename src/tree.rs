@@ -0,0 +1,508 @@
+use crate::{Span, TokenStream, WithSpan};
+use alloc::vec::Vec;
+
+/// `TokenTree` is a token, or a group of tokens between a pair of delimiters
+///
+/// This is created with [`TokenTree::build`](#method.build)
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenTree<T> {
+    /// A single token
+    Leaf(WithSpan<T>),
+    /// A delimited group of trees
+    Group(Group<T>),
+}
+
+/// `Group` is the trees between a pair of delimiters in a [`TokenTree`](./enum.TokenTree.html)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group<T> {
+    /// The opening delimiter
+    pub open: WithSpan<T>,
+    /// The closing delimiter, if the group was closed
+    pub close: Option<WithSpan<T>>,
+    /// The trees inside of the group
+    pub children: Vec<TokenTree<T>>,
+}
+
+impl<T> Group<T> {
+    /// The span from the opening delimiter to the closing delimiter (or the last tree)
+    pub fn span(&self) -> Span {
+        let end = match (&self.close, self.children.last()) {
+            (Some(close), _) => close.span,
+            (None, Some(last)) => last.span(),
+            (None, None) => self.open.span,
+        };
+        self.open.span.join(end)
+    }
+}
+
+impl<T> TokenTree<T> {
+    /// The span of the tree
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Leaf(token) => token.span,
+            Self::Group(group) => group.span(),
+        }
+    }
+
+    /// Build trees from the `tokens`, grouping them with the `(open, close)` kinds in `pairs`
+    ///
+    /// Unclosed groups have no closing delimiter, and closers without an
+    /// opener are leaves. A closer for an outer group also closes the groups
+    /// inside of it. Use [`check_brackets`](./fn.check_brackets.html) to report these
+    ///
+    /// ```rust
+    /// # use logos::Logos;
+    /// # use logos_iterator::{lex, TokenTree};
+    /// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    /// enum Token {
+    ///     #[end]
+    ///     Eof,
+    ///     #[error]
+    ///     Unknown,
+    ///     #[token = "("]
+    ///     Open,
+    ///     #[token = ")"]
+    ///     Close,
+    ///     #[regex = "[0-9]"]
+    ///     Digit,
+    /// }
+    ///
+    /// let tokens = lex::<Token, _>("1 (2 (3)) 4").spanned();
+    /// let trees = TokenTree::build(tokens, &[(Token::Open, Token::Close)]);
+    /// assert_eq!(trees.len(), 3);
+    /// match &trees[1] {
+    ///     TokenTree::Group(group) => assert_eq!(group.children.len(), 2),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn build(tokens: impl IntoIterator<Item = WithSpan<T>>, pairs: &[(T, T)]) -> Vec<Self>
+    where
+        T: PartialEq,
+    {
+        // the open groups, with the index of their pair
+        let mut stack: Vec<(usize, Group<T>)> = vec![];
+        let mut trees = vec![];
+
+        fn push<T>(
+            stack: &mut [(usize, Group<T>)],
+            trees: &mut Vec<TokenTree<T>>,
+            tree: TokenTree<T>,
+        ) {
+            match stack.last_mut() {
+                Some((_, group)) => group.children.push(tree),
+                None => trees.push(tree),
+            }
+        }
+
+        for token in tokens {
+            if let Some(pair) = pairs.iter().position(|(open, _)| *open == token.item) {
+                let group = Group {
+                    open: token,
+                    close: None,
+                    children: vec![],
+                };
+                stack.push((pair, group));
+                continue;
+            }
+
+            let index = pairs
+                .iter()
+                .position(|(_, close)| *close == token.item)
+                .and_then(|pair| stack.iter().rposition(|&(open, _)| open == pair));
+
+            let index = match index {
+                Some(index) => index,
+                None => {
+                    push(&mut stack, &mut trees, TokenTree::Leaf(token));
+                    continue;
+                }
+            };
+
+            while stack.len() > index + 1 {
+                let (_, group) = stack.pop().unwrap();
+                push(&mut stack, &mut trees, TokenTree::Group(group));
+            }
+            let (_, mut group) = stack.pop().unwrap();
+            group.close.replace(token);
+            push(&mut stack, &mut trees, TokenTree::Group(group));
+        }
+
+        while let Some((_, group)) = stack.pop() {
+            push(&mut stack, &mut trees, TokenTree::Group(group));
+        }
+        trees
+    }
+}
+
+impl<T> TokenStream<T>
+where
+    T: PartialEq + Clone,
+{
+    /// Build [`TokenTree`](./enum.TokenTree.html)s from this stream
+    pub fn token_trees(&self, pairs: &[(T, T)]) -> Vec<TokenTree<T>> {
+        TokenTree::build(self.iter().cloned(), pairs)
+    }
+}
+
+/// `NodeId` is a tree in a [`TreeIndex`](./struct.TreeIndex.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+/// `TreeIndex` indexes [`TokenTree`](./enum.TokenTree.html)s, for going from a
+/// tree to its parent and siblings without walking from the roots
+///
+/// The trees are numbered in preorder, so a group comes before its children
+///
+/// ```rust
+/// # use logos_iterator::{Span, TokenTree, TreeIndex, WithSpan};
+/// let source = "a (b [c]) d";
+/// let tokens = source
+///     .char_indices()
+///     .filter(|(_, c)| *c != ' ')
+///     .map(|(i, c)| WithSpan::new(c, Span::from(i..i + 1)));
+/// let trees = TokenTree::build(tokens, &[('(', ')'), ('[', ']')]);
+/// let index = TreeIndex::new(&trees);
+///
+/// // find the groups around `c`
+/// let (c, _) = index
+///     .nodes()
+///     .find(|(_, tree)| matches!(tree, TokenTree::Leaf(k) if k.item == 'c'))
+///     .unwrap();
+/// let around = index
+///     .ancestors(c)
+///     .map(|node| index.tree(node).span())
+///     .collect::<Vec<_>>();
+/// assert_eq!(around, vec![Span::from(5..8), Span::from(2..9)]);
+///
+/// let group = index.parent(c).unwrap();
+/// let b = index.prev_sibling(group).unwrap();
+/// assert_eq!(index.tree(b).span(), Span::from(3..4));
+/// assert_eq!(index.next_sibling(group), None);
+/// ```
+#[derive(Debug)]
+pub struct TreeIndex<'a, T> {
+    nodes: Vec<Node<'a, T>>,
+}
+
+#[derive(Debug)]
+struct Node<'a, T> {
+    tree: &'a TokenTree<T>,
+    parent: Option<NodeId>,
+    prev: Option<NodeId>,
+    next: Option<NodeId>,
+}
+
+impl<'a, T> TreeIndex<'a, T> {
+    /// Index the `trees`
+    pub fn new(trees: &'a [TokenTree<T>]) -> Self {
+        fn visit<'a, T>(
+            nodes: &mut Vec<Node<'a, T>>,
+            trees: &'a [TokenTree<T>],
+            parent: Option<NodeId>,
+        ) {
+            let mut prev = None;
+            for tree in trees {
+                let id = NodeId(nodes.len());
+                if let Some(NodeId(prev)) = prev {
+                    nodes[prev].next = Some(id);
+                }
+                nodes.push(Node {
+                    tree,
+                    parent,
+                    prev,
+                    next: None,
+                });
+                if let TokenTree::Group(group) = tree {
+                    visit(nodes, &group.children, Some(id));
+                }
+                prev = Some(id);
+            }
+        }
+
+        let mut nodes = vec![];
+        visit(&mut nodes, trees, None);
+        Self { nodes }
+    }
+
+    /// The number of trees, including the ones in groups
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether there are no trees
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Iterate over all of the trees, in preorder
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &'a TokenTree<T>)> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (NodeId(i), node.tree))
+    }
+
+    /// Iterate over the trees that aren't in a group
+    pub fn roots(&self) -> impl Iterator<Item = NodeId> + '_ {
+        let first = Some(NodeId(0)).filter(|_| !self.nodes.is_empty());
+        core::iter::successors(first, move |&node| self.next_sibling(node))
+    }
+
+    /// The tree of the `node`
+    ///
+    /// # Panics
+    /// This panics if the `node` isn't from this index
+    pub fn tree(&self, node: NodeId) -> &'a TokenTree<T> {
+        self.nodes[node.0].tree
+    }
+
+    /// The group that the `node` is in, if it isn't a root
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        self.nodes[node.0].parent
+    }
+
+    /// The tree after the `node`, in the same group (or in the roots)
+    pub fn next_sibling(&self, node: NodeId) -> Option<NodeId> {
+        self.nodes[node.0].next
+    }
+
+    /// The tree before the `node`, in the same group (or in the roots)
+    pub fn prev_sibling(&self, node: NodeId) -> Option<NodeId> {
+        self.nodes[node.0].prev
+    }
+
+    /// Iterate over the groups that the `node` is in, from the innermost
+    pub fn ancestors(&self, node: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        core::iter::successors(self.parent(node), move |&node| self.parent(node))
+    }
+
+    /// Iterate over the trees in the `node`'s group, if it is one
+    pub fn children(&self, node: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let first = match self.nodes[node.0].tree {
+            TokenTree::Group(group) if !group.children.is_empty() => Some(NodeId(node.0 + 1)),
+            _ => None,
+        };
+        core::iter::successors(first, move |&node| self.next_sibling(node))
+    }
+}
+
+impl<'a, T> TreeIndex<'a, T> {
+    /// The groups opened by a delimiter of the `kind`
+    ///
+    /// This and the other queries yield the nodes that match in preorder, with
+    /// the span of their tree
+    ///
+    /// ```rust
+    /// # use logos_iterator::{Span, TokenTree, TreeIndex, WithSpan};
+    /// let source = "f(a, [b]) [c]";
+    /// let tokens = source
+    ///     .char_indices()
+    ///     .filter(|(_, c)| *c != ' ')
+    ///     .map(|(i, c)| WithSpan::new(c, Span::from(i..i + 1)));
+    /// let trees = TokenTree::build(tokens, &[('(', ')'), ('[', ']')]);
+    /// let index = TreeIndex::new(&trees);
+    ///
+    /// let spans = |nodes: &mut dyn Iterator<Item = WithSpan<_>>| nodes.map(|k| k.span).collect::<Vec<_>>();
+    /// assert_eq!(spans(&mut index.groups_with_delimiter(&'[')), vec![Span::from(5..8), Span::from(10..13)]);
+    /// assert_eq!(spans(&mut index.leaves_of_kind(&',')), vec![Span::from(3..4)]);
+    /// assert_eq!(
+    ///     spans(&mut index.descendants_in_span(Span::from(4..13))),
+    ///     vec![Span::from(5..8), Span::from(6..7), Span::from(10..13), Span::from(11..12)]
+    /// );
+    /// ```
+    pub fn groups_with_delimiter<'b>(
+        &'b self,
+        kind: &'b T,
+    ) -> impl Iterator<Item = WithSpan<NodeId>> + 'b
+    where
+        T: PartialEq,
+    {
+        self.matching(
+            move |tree| matches!(tree, TokenTree::Group(group) if group.open.item == *kind),
+        )
+    }
+
+    /// The leaves of the `kind`
+    pub fn leaves_of_kind<'b>(&'b self, kind: &'b T) -> impl Iterator<Item = WithSpan<NodeId>> + 'b
+    where
+        T: PartialEq,
+    {
+        self.matching(move |tree| matches!(tree, TokenTree::Leaf(token) if token.item == *kind))
+    }
+
+    /// The trees whose spans are entirely within `span`
+    ///
+    /// This includes the trees in those groups
+    pub fn descendants_in_span(&self, span: Span) -> impl Iterator<Item = WithSpan<NodeId>> + '_ {
+        self.nodes().filter_map(move |(node, tree)| {
+            let inner = tree.span();
+            if span.start <= inner.start && inner.end <= span.end {
+                Some(WithSpan::new(node, inner))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn matching<'b>(
+        &'b self,
+        mut predicate: impl FnMut(&TokenTree<T>) -> bool + 'b,
+    ) -> impl Iterator<Item = WithSpan<NodeId>> + 'b {
+        self.nodes()
+            .filter(move |(_, tree)| predicate(tree))
+            .map(|(node, tree)| WithSpan::new(node, tree.span()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[token = "("]
+        OpenParen,
+        #[token = ")"]
+        CloseParen,
+        #[token = "{"]
+        OpenBrace,
+        #[token = "}"]
+        CloseBrace,
+        #[regex = "[a-z]"]
+        Letter,
+    }
+
+    const PAIRS: [(Token, Token); 2] = [
+        (Token::OpenParen, Token::CloseParen),
+        (Token::OpenBrace, Token::CloseBrace),
+    ];
+
+    fn shape(trees: &[TokenTree<Token>]) -> String {
+        trees
+            .iter()
+            .map(|tree| match tree {
+                TokenTree::Leaf(token) => format!("{:?}", token.item),
+                TokenTree::Group(group) => format!(
+                    "[{} {}{}]",
+                    group.span(),
+                    shape(&group.children),
+                    if group.close.is_some() {
+                        ""
+                    } else {
+                        " unclosed"
+                    }
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn build() {
+        let build = |input| {
+            let stream = crate::lex::<Token, _>(input)
+                .spanned()
+                .collect::<TokenStream<_>>();
+            shape(&stream.token_trees(&PAIRS))
+        };
+
+        assert_eq!(
+            build("a (b {c}) d"),
+            "Letter [2..9 Letter [5..8 Letter]] Letter"
+        );
+        assert_eq!(build("a ) b"), "Letter CloseParen Letter");
+        assert_eq!(build("( { a )"), "[0..7 [2..5 Letter unclosed]]");
+        assert_eq!(
+            build("( a { b"),
+            "[0..7 Letter [4..7 Letter unclosed] unclosed]"
+        );
+    }
+
+    #[test]
+    fn index() {
+        let stream = crate::lex::<Token, _>("a(b{c}d)e")
+            .spanned()
+            .collect::<TokenStream<_>>();
+        let trees = stream.token_trees(&PAIRS);
+        let index = TreeIndex::new(&trees);
+        assert_eq!(index.len(), 7);
+        assert_eq!(
+            index
+                .nodes()
+                .filter(|&(node, _)| index.parent(node).is_none())
+                .count(),
+            index.roots().count()
+        );
+
+        let spans = |nodes: &mut dyn Iterator<Item = NodeId>| {
+            nodes
+                .map(|node| index.tree(node).span())
+                .collect::<Vec<_>>()
+        };
+        let roots = index.roots().collect::<Vec<_>>();
+        assert_eq!(
+            spans(&mut roots.iter().copied()),
+            vec![Span::from(0..1), Span::from(1..8), Span::from(8..9)]
+        );
+
+        let group = roots[1];
+        assert_eq!(
+            spans(&mut index.children(group)),
+            vec![Span::from(2..3), Span::from(3..6), Span::from(6..7)]
+        );
+        let inner = index.children(group).nth(1).unwrap();
+        let c = index.children(inner).next().unwrap();
+        assert_eq!(index.ancestors(c).collect::<Vec<_>>(), vec![inner, group]);
+        assert_eq!((index.prev_sibling(c), index.next_sibling(c)), (None, None));
+        assert_eq!(index.children(c).count(), 0);
+
+        let last = roots[2];
+        assert_eq!(index.prev_sibling(last), Some(group));
+        assert_eq!(index.ancestors(last).count(), 0);
+        assert!(TreeIndex::<Token>::new(&[]).is_empty());
+    }
+
+    #[test]
+    fn queries() {
+        let stream = crate::lex::<Token, _>("(a{b}) {c (d)")
+            .spanned()
+            .collect::<TokenStream<_>>();
+        let trees = stream.token_trees(&PAIRS);
+        let index = TreeIndex::new(&trees);
+
+        let spans = |nodes: &mut dyn Iterator<Item = WithSpan<NodeId>>| {
+            nodes.map(|k| k.span).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            spans(&mut index.groups_with_delimiter(&Token::OpenParen)),
+            vec![Span::from(0..6), Span::from(10..13)]
+        );
+        // an unclosed group still spans to its last tree
+        assert_eq!(
+            spans(&mut index.groups_with_delimiter(&Token::OpenBrace)),
+            vec![Span::from(2..5), Span::from(7..13)]
+        );
+        assert_eq!(index.leaves_of_kind(&Token::Letter).count(), 4);
+        assert_eq!(index.leaves_of_kind(&Token::CloseBrace).count(), 0);
+
+        let inside = index
+            .descendants_in_span(Span::from(1..9))
+            .map(|k| index.tree(k.item).span())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            inside,
+            vec![
+                Span::from(1..2),
+                Span::from(2..5),
+                Span::from(3..4),
+                Span::from(8..9)
+            ]
+        );
+        assert_eq!(index.descendants_in_span(Span::from(20..30)).count(), 0);
+    }
+}
@@ -0,0 +1,341 @@
+use crate::{Span, WithSpan};
+use alloc::{string::String, vec::Vec};
+
+/// `Attached` is a token along with the trivia around it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attached<T> {
+    /// The trivia before the leading trivia, separated from the token by a blank line
+    ///
+    /// This is only used with
+    /// [`TriviaAttacher::with_detach_blank_lines`](./struct.TriviaAttacher.html#method.with_detach_blank_lines)
+    pub detached: Vec<WithSpan<T>>,
+    /// The trivia before the token, that wasn't trailing the previous token
+    pub leading: Vec<WithSpan<T>>,
+    pub token: WithSpan<T>,
+    /// The trivia after the token, up to and including the end of its line
+    pub trailing: Vec<WithSpan<T>>,
+}
+
+impl<T> Attached<T> {
+    /// The span covering the token and its trivia
+    pub fn span(&self) -> Span {
+        self.pieces()
+            .map(|k| k.span)
+            .fold(self.token.span, Span::join)
+    }
+
+    /// Iterate over the detached and leading trivia, the token and the trailing trivia, in order
+    pub fn pieces(&self) -> impl Iterator<Item = &WithSpan<T>> + '_ {
+        self.detached
+            .iter()
+            .chain(&self.leading)
+            .chain(Some(&self.token))
+            .chain(&self.trailing)
+    }
+}
+
+/// `AttachPolicy` decides which token trivia between two tokens is attached to
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum AttachPolicy {
+    /// All of the trivia leads the following token
+    Following,
+    /// Trivia on the same line as the previous token trails it, and the rest
+    /// leads the following token
+    #[default]
+    SameLine,
+}
+
+/// `TriviaAttacher` builds a [`TriviaStream`](./struct.TriviaStream.html) with an
+/// [`AttachPolicy`](./enum.AttachPolicy.html)
+///
+/// ```rust
+/// # use logos_iterator::{AttachPolicy, Span, TriviaAttacher, WithSpan};
+/// // words and comments, separated by whitespace that the lexer skipped
+/// let source = "a // one\n// two\n\n// three\nb";
+/// let tokens = vec![(0, 1), (2, 8), (9, 15), (17, 25), (26, 27)]
+///     .into_iter()
+///     .map(|(start, end)| WithSpan::new(source.as_bytes()[start] == b'/', Span { start, end }));
+///
+/// let stream = TriviaAttacher::new(|is_comment: &bool| *is_comment)
+///     .with_policy(AttachPolicy::Following)
+///     .with_detach_blank_lines(true)
+///     .attach(source, tokens);
+///
+/// let b = &stream.tokens()[1];
+/// assert_eq!(&source[b.detached[1].span], "// two");
+/// assert_eq!(&source[b.leading[0].span], "// three");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct TriviaAttacher<F> {
+    is_trivia: F,
+    policy: AttachPolicy,
+    detach_blank_lines: bool,
+}
+
+impl<F> TriviaAttacher<F> {
+    /// Create an attacher, where `is_trivia` returns `true` for trivia
+    ///
+    /// This uses [`AttachPolicy::SameLine`](./enum.AttachPolicy.html#variant.SameLine)
+    /// and doesn't detach trivia
+    pub fn new(is_trivia: F) -> Self {
+        Self {
+            is_trivia,
+            policy: AttachPolicy::default(),
+            detach_blank_lines: false,
+        }
+    }
+
+    /// Set the policy for attaching trivia
+    pub fn with_policy(mut self, policy: AttachPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Whether the trivia that would lead a token, but is separated from it by a
+    /// blank line, should be [`detached`](./struct.Attached.html#structfield.detached)
+    pub fn with_detach_blank_lines(mut self, detach: bool) -> Self {
+        self.detach_blank_lines = detach;
+        self
+    }
+
+    /// Attach the trivia in `tokens` from `source`
+    pub fn attach<T>(
+        &self,
+        source: &str,
+        tokens: impl IntoIterator<Item = WithSpan<T>>,
+    ) -> TriviaStream<T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let has_newline =
+            |start: usize, end: usize| source.get(start..end).is_some_and(|s| s.contains('\n'));
+
+        let mut attached: Vec<Attached<T>> = vec![];
+        let mut pending = vec![];
+        // whether the last token's line has ended
+        let mut line_ended = true;
+        let mut prev_end = 0;
+
+        for piece in tokens {
+            if has_newline(prev_end, piece.span.start) {
+                line_ended = true;
+            }
+            prev_end = piece.span.end;
+
+            if !(self.is_trivia)(&piece.item) {
+                let mut leading = core::mem::take(&mut pending);
+                let detached = if self.detach_blank_lines {
+                    let at = detach_point(source, &leading, piece.span.start);
+                    let rest = leading.split_off(at);
+                    core::mem::replace(&mut leading, rest)
+                } else {
+                    vec![]
+                };
+                attached.push(Attached {
+                    detached,
+                    leading,
+                    token: piece,
+                    trailing: vec![],
+                });
+                line_ended = false;
+                continue;
+            }
+
+            match attached.last_mut() {
+                Some(last) if !line_ended && self.policy == AttachPolicy::SameLine => {
+                    line_ended = has_newline(piece.span.start, piece.span.end);
+                    last.trailing.push(piece)
+                }
+                _ => pending.push(piece),
+            }
+        }
+
+        TriviaStream {
+            tokens: attached,
+            eof: pending,
+        }
+    }
+}
+
+// the number of the `leading` trivia that are before the last blank line before `end`
+fn detach_point<T>(source: &str, leading: &[WithSpan<T>], end: usize) -> usize {
+    let newlines = |start: usize, end: usize| {
+        source
+            .get(start..end)
+            .map_or(0, |s| s.matches('\n').count())
+    };
+
+    let mut end = end;
+    let mut count = 0;
+    for (i, piece) in leading.iter().enumerate().rev() {
+        count += newlines(piece.span.end, end);
+        let text = source.get(piece.span.start..piece.span.end).unwrap_or("");
+        if !text.trim().is_empty() {
+            if count >= 2 {
+                return i + 1;
+            }
+            count = 0;
+        }
+        count += text.matches('\n').count();
+        end = piece.span.start;
+    }
+    0
+}
+
+/// `TriviaStream` is a stream of tokens with their trivia (e.g. comments and newlines) attached
+///
+/// Trivia after a token on the same line trails it, and the rest lead the next
+/// token. Trivia after the last token are kept separately, as the
+/// [`eof`](#method.eof) trivia. Use a [`TriviaAttacher`](./struct.TriviaAttacher.html)
+/// to attach them differently
+///
+/// ```rust
+/// # use logos::Logos;
+/// # use logos_iterator::{lex, TriviaStream};
+/// #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+/// enum Token {
+///     #[end]
+///     Eof,
+///     #[error]
+///     Unknown,
+///     #[regex = "[a-z]+"]
+///     Word,
+///     #[regex = "#[^\n]*"]
+///     Comment,
+///     #[token = "\n"]
+///     NewLine,
+/// }
+///
+/// let source = "# header\na # first\n  b\n# end";
+/// let tokens = lex::<Token, _>(source).spanned();
+/// let stream = TriviaStream::attach(source, tokens, |k| *k != Token::Word);
+///
+/// let a = &stream.tokens()[0];
+/// assert_eq!(a.leading.len(), 2);
+/// assert_eq!(&source[a.trailing[0].span], "# first");
+/// assert_eq!(stream.eof().len(), 1);
+///
+/// assert_eq!(stream.print(source), source);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TriviaStream<T> {
+    tokens: Vec<Attached<T>>,
+    eof: Vec<WithSpan<T>>,
+}
+
+impl<T> TriviaStream<T> {
+    /// Attach the trivia in `tokens` from `source`, where `is_trivia` returns `true` for trivia
+    pub fn attach(
+        source: &str,
+        tokens: impl IntoIterator<Item = WithSpan<T>>,
+        is_trivia: impl Fn(&T) -> bool,
+    ) -> Self {
+        TriviaAttacher::new(is_trivia).attach(source, tokens)
+    }
+
+    /// The tokens, with their trivia
+    pub fn tokens(&self) -> &[Attached<T>] {
+        &self.tokens
+    }
+
+    /// The trivia after the last token's line
+    pub fn eof(&self) -> &[WithSpan<T>] {
+        &self.eof
+    }
+
+    /// Iterate over all of the tokens and trivia, in order
+    pub fn pieces(&self) -> impl Iterator<Item = &WithSpan<T>> + '_ {
+        self.tokens
+            .iter()
+            .flat_map(Attached::pieces)
+            .chain(&self.eof)
+    }
+
+    /// Reassemble the source from the tokens and trivia
+    ///
+    /// The text that the lexer skipped between pieces (and after the last one)
+    /// is taken from `source`, so this reproduces `source` exactly when no
+    /// pieces were removed or reordered
+    pub fn print(&self, source: &str) -> String {
+        let mut out = String::with_capacity(source.len());
+        let mut pos = 0;
+        for piece in self.pieces() {
+            let start = pos.min(piece.span.start);
+            out.push_str(source.get(start..piece.span.end).unwrap_or(""));
+            pos = pos.max(piece.span.end);
+        }
+        out.push_str(source.get(pos..).unwrap_or(""));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logos::Logos;
+
+    #[derive(Logos, PartialEq, Clone, Copy, Debug)]
+    enum Token {
+        #[end]
+        Eof,
+        #[error]
+        Unknown,
+        #[regex = "[a-z]+"]
+        Word,
+        #[regex = "//[^\n]*"]
+        Comment,
+        #[token = "\n"]
+        NewLine,
+    }
+
+    #[test]
+    fn attach() {
+        let source = "a // one\n\n// two\nb c // three\n";
+        let tokens = crate::lex::<Token, _>(source).spanned();
+        let stream = TriviaStream::attach(source, tokens, |k| {
+            matches!(k, Token::Comment | Token::NewLine)
+        });
+
+        let texts =
+            |pieces: &[WithSpan<Token>]| pieces.iter().map(|k| &source[k.span]).collect::<Vec<_>>();
+        let tokens = stream.tokens();
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(texts(&tokens[0].trailing), vec!["// one", "\n"]);
+        assert_eq!(texts(&tokens[1].leading), vec!["\n", "// two", "\n"]);
+        assert!(tokens[1].trailing.is_empty());
+        assert_eq!(texts(&tokens[2].trailing), vec!["// three", "\n"]);
+        assert!(stream.eof().is_empty());
+        assert_eq!(tokens[1].span(), Span::from(9..18));
+
+        assert_eq!(stream.print(source), source);
+    }
+
+    #[test]
+    fn policies() {
+        let source = "a // one\n// two\n\n\n// three\nb\n\n// four\n";
+        let tokens = crate::lex::<Token, _>(source).spanned().collect::<Vec<_>>();
+        let texts =
+            |pieces: &[WithSpan<Token>]| pieces.iter().map(|k| &source[k.span]).collect::<Vec<_>>();
+        let attacher =
+            TriviaAttacher::new(|k: &Token| matches!(k, Token::Comment | Token::NewLine));
+
+        let stream = attacher
+            .with_policy(AttachPolicy::Following)
+            .attach(source, tokens.clone());
+        let b = &stream.tokens()[1];
+        assert!(stream.tokens()[0].trailing.is_empty());
+        assert!(b.detached.is_empty());
+        assert_eq!(b.leading.len(), 8);
+        assert_eq!(texts(stream.eof()), vec!["\n", "\n", "// four", "\n"]);
+        assert_eq!(stream.print(source), source);
+
+        let stream = attacher
+            .with_detach_blank_lines(true)
+            .attach(source, tokens);
+        let b = &stream.tokens()[1];
+        assert_eq!(texts(&stream.tokens()[0].trailing), vec!["// one", "\n"]);
+        assert_eq!(texts(&b.detached), vec!["// two"]);
+        assert_eq!(texts(&b.leading), vec!["\n", "\n", "\n", "// three", "\n"]);
+        assert_eq!(stream.print(source), source);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{Span, WithSpan};
+use alloc::vec::Vec;
+
+/// `SpanProblem` is something wrong with the spans of a token stream, as found
+/// by a [`SpanValidator`](./struct.SpanValidator.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpanProblem {
+    /// The token at `index` starts before the one before it
+    OutOfOrder {
+        index: usize,
+        span: Span,
+        previous: Span,
+    },
+    /// The span of the token at `index` ends before it starts
+    Reversed { index: usize, span: Span },
+    /// The span of the token at `index` ends past the end of the source
+    OutOfBounds { index: usize, span: Span },
+    /// The `offset` of the span of the token at `index` isn't on a `char` boundary
+    NotCharBoundary {
+        index: usize,
+        span: Span,
+        offset: usize,
+    },
+    /// The `span` of the source isn't covered by any token
+    Gap { span: Span },
+}
+
+impl core::fmt::Display for SpanProblem {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::OutOfOrder {
+                index,
+                span,
+                previous,
+            } => write!(
+                f,
+                "token {} at {} starts before the token before it at {}",
+                index, span, previous
+            ),
+            Self::Reversed { index, span } => {
+                write!(f, "token {} at {} ends before it starts", index, span)
+            }
+            Self::OutOfBounds { index, span } => {
+                write!(f, "token {} at {} is out of bounds", index, span)
+            }
+            Self::NotCharBoundary {
+                index,
+                span,
+                offset,
+            } => write!(
+                f,
+                "token {} at {} isn't on a char boundary at offset {}",
+                index, span, offset
+            ),
+            Self::Gap { span } => write!(f, "{} isn't covered by a token", span),
+        }
+    }
+}
+
+/// `SpanReport` is the problems found by a [`SpanValidator`](./struct.SpanValidator.html)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpanReport {
+    /// The problems, in the order of the tokens
+    pub problems: Vec<SpanProblem>,
+}
+
+impl SpanReport {
+    /// Whether there were no problems
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl core::fmt::Display for SpanReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_ok() {
+            return f.write_str("the spans are valid");
+        }
+        let len = self.problems.len();
+        let plural = if len == 1 { "" } else { "s" };
+        write!(f, "found {} problem{} with the spans", len, plural)?;
+        for problem in &self.problems {
+            write!(f, "\n  {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+/// `SpanValidator` checks the spans of a token stream
+///
+/// The tokens must start in order (but can overlap), and their spans must not
+/// be reversed or end past the end of the source. With a `str` source, the
+/// spans must be on `char` boundaries. The gaps between the tokens can also
+/// be reported, for lexers that shouldn't skip anything
+///
+/// ```rust
+/// # use logos_iterator::{Span, SpanProblem, SpanValidator, WithSpan};
+/// let source = "ab é";
+/// let tokens = vec![
+///     WithSpan::new('a', Span::from(0..1)),
+///     WithSpan::new('b', Span::from(1..2)),
+///     WithSpan::new('é', Span::from(3..4)),
+/// ];
+///
+/// let report = SpanValidator::for_source(source).with_coverage(true).validate(&tokens);
+/// assert_eq!(
+///     report.problems,
+///     vec![
+///         SpanProblem::Gap { span: Span::from(2..3) },
+///         SpanProblem::NotCharBoundary { index: 2, span: Span::from(3..4), offset: 4 },
+///         SpanProblem::Gap { span: Span::from(4..5) },
+///     ]
+/// );
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct SpanValidator<'a> {
+    len: usize,
+    source: Option<&'a str>,
+    coverage: bool,
+}
+
+impl<'a> SpanValidator<'a> {
+    /// Create a validator for a source that is `len` bytes long
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            source: None,
+            coverage: false,
+        }
+    }
+
+    /// Create a validator for `source`, which also checks the `char` boundaries
+    pub fn for_source(source: &'a str) -> Self {
+        Self {
+            source: Some(source),
+            ..Self::new(source.len())
+        }
+    }
+
+    /// Report the parts of the source that aren't covered by a token, if `coverage` is `true`
+    pub fn with_coverage(mut self, coverage: bool) -> Self {
+        self.coverage = coverage;
+        self
+    }
+
+    /// Check the spans of the `tokens`
+    pub fn validate<T>(&self, tokens: &[WithSpan<T>]) -> SpanReport {
+        let mut problems = vec![];
+        // the end of the source covered so far
+        let mut covered = 0;
+        let gap = |problems: &mut Vec<_>, covered: usize, start: usize| {
+            if self.coverage && covered < start {
+                problems.push(SpanProblem::Gap {
+                    span: Span::new(covered, start),
+                });
+            }
+        };
+
+        for (index, token) in tokens.iter().enumerate() {
+            let span = token.span;
+            if let Some(previous) = index.checked_sub(1).map(|i| tokens[i].span) {
+                if span.start < previous.start {
+                    problems.push(SpanProblem::OutOfOrder {
+                        index,
+                        span,
+                        previous,
+                    });
+                }
+            }
+            if span.end < span.start {
+                problems.push(SpanProblem::Reversed { index, span });
+                continue;
+            }
+            if span.end > self.len {
+                problems.push(SpanProblem::OutOfBounds { index, span });
+                continue;
+            }
+
+            gap(&mut problems, covered, span.start);
+            covered = covered.max(span.end);
+
+            let source = match self.source {
+                Some(source) => source,
+                None => continue,
+            };
+            if let Some(offset) = [span.start, span.end]
+                .iter()
+                .copied()
+                .find(|&offset| !source.is_char_boundary(offset))
+            {
+                problems.push(SpanProblem::NotCharBoundary {
+                    index,
+                    span,
+                    offset,
+                });
+            }
+        }
+
+        gap(&mut problems, covered, self.len);
+        SpanReport { problems }
+    }
+}
+
+/// Check the spans of the `tokens`, for a source that is `source_len` bytes long
+///
+/// See [`SpanValidator`](./struct.SpanValidator.html) for what is checked, and
+/// for checking the `char` boundaries and coverage
+///
+/// ```rust
+/// # use logos_iterator::{validate, Span, SpanProblem, WithSpan};
+/// let tokens = vec![
+///     WithSpan::new((), Span::from(2..3)),
+///     WithSpan::new((), Span::from(0..1)),
+///     WithSpan::new((), Span::from(4..9)),
+/// ];
+/// let report = validate(&tokens, 5);
+/// assert_eq!(
+///     report.problems,
+///     vec![
+///         SpanProblem::OutOfOrder { index: 1, span: Span::from(0..1), previous: Span::from(2..3) },
+///         SpanProblem::OutOfBounds { index: 2, span: Span::from(4..9) },
+///     ]
+/// );
+/// ```
+pub fn validate<T>(tokens: &[WithSpan<T>], source_len: usize) -> SpanReport {
+    SpanValidator::new(source_len).validate(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(spans: &[(usize, usize)]) -> Vec<WithSpan<()>> {
+        spans
+            .iter()
+            .map(|&(start, end)| WithSpan::new((), Span { start, end }))
+            .collect()
+    }
+
+    #[test]
+    fn validate_spans() {
+        let source = "let x = 1;";
+        let stream = crate::TokenStream::new(tokens(&[(0, 3), (4, 5), (6, 7), (8, 9), (9, 10)]));
+        assert!(validate(&stream, source.len()).is_ok());
+        assert!(SpanValidator::for_source(source).validate(&stream).is_ok());
+
+        let report = SpanValidator::new(source.len())
+            .with_coverage(true)
+            .validate(&stream);
+        assert_eq!(report.problems.len(), 3);
+        assert_eq!(
+            report.problems[0],
+            SpanProblem::Gap {
+                span: Span::from(3..4)
+            }
+        );
+
+        // overlapping tokens cover the source
+        let overlapping = tokens(&[(0, 4), (1, 2), (3, 5)]);
+        let report = SpanValidator::new(5)
+            .with_coverage(true)
+            .validate(&overlapping);
+        assert!(report.is_ok(), "{}", report);
+
+        let report = validate(&tokens(&[(2, 1), (3, 4)]), 4);
+        assert_eq!(
+            report.to_string(),
+            "found 1 problem with the spans\n  token 0 at 2..1 ends before it starts"
+        );
+        assert_eq!(SpanReport::default().to_string(), "the spans are valid");
+
+        // an empty source is covered by no tokens
+        let report = SpanValidator::for_source("")
+            .with_coverage(true)
+            .validate::<()>(&[]);
+        assert!(report.is_ok());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{Group, TokenTree, WithSpan};
+
+/// `TokenVisitor` visits each token in a stream of spanned tokens
+///
+/// Override the methods for the parts that are interesting. The default
+/// [`visit_stream`](#method.visit_stream) calls [`walk_stream`](./fn.walk_stream.html)
+pub trait TokenVisitor<T> {
+    /// Visit a stream of tokens
+    fn visit_stream(&mut self, tokens: &[WithSpan<T>]) {
+        walk_stream(self, tokens)
+    }
+
+    /// Visit a single token
+    fn visit_token(&mut self, token: &WithSpan<T>) {
+        let _ = token;
+    }
+}
+
+/// Visit each token in `tokens` with the `visitor`
+pub fn walk_stream<T, V>(visitor: &mut V, tokens: &[WithSpan<T>])
+where
+    V: TokenVisitor<T> + ?Sized,
+{
+    for token in tokens {
+        visitor.visit_token(token)
+    }
+}
+
+/// `TokenTreeVisitor` visits each tree in a [`TokenTree`](./enum.TokenTree.html)
+///
+/// Override the methods for the parts that are interesting. The default methods
+/// call the `walk_` functions, which visit the children
+///
+/// ```rust
+/// # use logos_iterator::{Group, Span, TokenTree, TokenTreeVisitor, WithSpan, walk_group};
+/// #[derive(Default)]
+/// struct MaxDepth {
+///     depth: usize,
+///     max: usize,
+/// }
+///
+/// impl<T> TokenTreeVisitor<T> for MaxDepth {
+///     fn visit_group(&mut self, group: &Group<T>) {
+///         self.depth += 1;
+///         self.max = self.max.max(self.depth);
+///         walk_group(self, group);
+///         self.depth -= 1;
+///     }
+/// }
+///
+/// let token = |c, i| WithSpan::new(c, Span::from(i..i + 1));
+/// let tokens = vec![token('(', 0), token('(', 1), token(')', 2), token(')', 3), token('(', 4)];
+/// let trees = TokenTree::build(tokens, &[('(', ')')]);
+///
+/// let mut visitor = MaxDepth::default();
+/// visitor.visit_trees(&trees);
+/// assert_eq!(visitor.max, 2);
+/// ```
+pub trait TokenTreeVisitor<T> {
+    /// Visit a sequence of trees
+    fn visit_trees(&mut self, trees: &[TokenTree<T>]) {
+        walk_trees(self, trees)
+    }
+
+    /// Visit a tree
+    fn visit_tree(&mut self, tree: &TokenTree<T>) {
+        walk_tree(self, tree)
+    }
+
+    /// Visit a group
+    fn visit_group(&mut self, group: &Group<T>) {
+        walk_group(self, group)
+    }
+
+    /// Visit a leaf
+    fn visit_leaf(&mut self, token: &WithSpan<T>) {
+        let _ = token;
+    }
+}
+
+/// Visit each of the `trees` with the `visitor`
+pub fn walk_trees<T, V>(visitor: &mut V, trees: &[TokenTree<T>])
+where
+    V: TokenTreeVisitor<T> + ?Sized,
+{
+    for tree in trees {
+        visitor.visit_tree(tree)
+    }
+}
+
+/// Visit the leaf or group in `tree` with the `visitor`
+pub fn walk_tree<T, V>(visitor: &mut V, tree: &TokenTree<T>)
+where
+    V: TokenTreeVisitor<T> + ?Sized,
+{
+    match tree {
+        TokenTree::Leaf(token) => visitor.visit_leaf(token),
+        TokenTree::Group(group) => visitor.visit_group(group),
+    }
+}
+
+/// Visit the children of `group` with the `visitor`
+///
+/// The delimiters aren't visited
+pub fn walk_group<T, V>(visitor: &mut V, group: &Group<T>)
+where
+    V: TokenTreeVisitor<T> + ?Sized,
+{
+    visitor.visit_trees(&group.children)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visitors() {
+        struct Count(usize, usize);
+        impl TokenVisitor<char> for Count {
+            fn visit_token(&mut self, token: &WithSpan<char>) {
+                self.0 += token.span.end - token.span.start;
+            }
+        }
+        impl TokenTreeVisitor<char> for Count {
+            fn visit_leaf(&mut self, _: &WithSpan<char>) {
+                self.0 += 1;
+            }
+            fn visit_group(&mut self, group: &Group<char>) {
+                self.1 += 1;
+                walk_group(self, group)
+            }
+        }
+
+        let tokens = crate::testing::chars("a(b(c)d)e");
+
+        let mut count = Count(0, 0);
+        count.visit_stream(&tokens);
+        assert_eq!(count.0, 9);
+
+        let trees = TokenTree::build(tokens, &[('(', ')')]);
+        let mut count = Count(0, 0);
+        count.visit_trees(&trees);
+        assert_eq!((count.0, count.1), (5, 2));
+    }
+}
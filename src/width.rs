@@ -0,0 +1,133 @@
+// an approximation of the display width of a char in a terminal
+//
+// this covers the east asian wide/fullwidth blocks, the common emoji blocks and
+// the combining/zero width chars that are drawn as part of the previous char.
+const ZERO: &[(u32, u32)] = &[
+    (0x0300, 0x036F),
+    (0x0483, 0x0489),
+    (0x0591, 0x05BD),
+    (0x0610, 0x061A),
+    (0x064B, 0x065F),
+    (0x0E31, 0x0E31),
+    (0x0E34, 0x0E3A),
+    (0x0E47, 0x0E4E),
+    (0x1160, 0x11FF),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200B, 0x200F),
+    (0x20D0, 0x20FF),
+    (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F),
+    (0xFEFF, 0xFEFF),
+    (0x1F3FB, 0x1F3FF),
+    (0xE0000, 0xE0FFF),
+];
+
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F),
+    (0x231A, 0x231B),
+    (0x23E9, 0x23EC),
+    (0x25FD, 0x25FE),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x26AA, 0x26AB),
+    (0x26BD, 0x26BE),
+    (0x26C4, 0x26C5),
+    (0x26F2, 0x26F5),
+    (0x2705, 0x2705),
+    (0x2728, 0x2728),
+    (0x274C, 0x274C),
+    (0x2753, 0x2755),
+    (0x2795, 0x2797),
+    (0x2B1B, 0x2B1C),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xA960, 0xA97F),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE10, 0xFE19),
+    (0xFE30, 0xFE6F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x1F004, 0x1F004),
+    (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A),
+    (0x1F200, 0x1F251),
+    (0x1F300, 0x1F64F),
+    (0x1F680, 0x1F6FF),
+    (0x1F7E0, 0x1F7EB),
+    (0x1F90C, 0x1F9FF),
+    (0x1FA70, 0x1FAFF),
+    (0x20000, 0x2FFFD),
+    (0x30000, 0x3FFFD),
+];
+
+const ZWJ: char = '\u{200D}';
+
+fn contains(table: &[(u32, u32)], c: char) -> bool {
+    let c = c as u32;
+    table
+        .binary_search_by(|&(lo, hi)| {
+            if hi < c {
+                core::cmp::Ordering::Less
+            } else if lo > c {
+                core::cmp::Ordering::Greater
+            } else {
+                core::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+/// Tracks the display width of chars, treating a char joined by a ZWJ as part
+/// of the previous one
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(crate) struct Width {
+    joined: bool,
+}
+
+impl Width {
+    pub(crate) fn of(&mut self, c: char) -> usize {
+        let joined = core::mem::replace(&mut self.joined, c == ZWJ);
+        if joined || c == ZWJ || contains(ZERO, c) {
+            0
+        } else if contains(WIDE, c) {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+/// Visit each char in `bytes` with its offset and length, an invalid byte is
+/// visited as `None`
+pub(crate) fn for_each_char(bytes: &[u8], mut f: impl FnMut(usize, usize, Option<char>)) {
+    let (mut rest, mut base) = (bytes, 0);
+    loop {
+        let (valid, skip) = match core::str::from_utf8(rest) {
+            Ok(valid) => (valid, 0),
+            Err(err) => {
+                let valid = core::str::from_utf8(&rest[..err.valid_up_to()]).unwrap();
+                (valid, err.error_len().unwrap_or(rest.len() - valid.len()))
+            }
+        };
+
+        for (i, c) in valid.char_indices() {
+            f(base + i, c.len_utf8(), Some(c))
+        }
+        base += valid.len();
+        for i in 0..skip {
+            f(base + i, 1, None)
+        }
+        base += skip;
+
+        if base == bytes.len() {
+            break;
+        }
+        rest = &bytes[base..];
+    }
+}